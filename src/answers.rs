@@ -0,0 +1,168 @@
+use crate::runner::{Error, Part, Solution, DAYS};
+use std::collections::BTreeMap;
+use std::fmt;
+use std::str::FromStr;
+
+/// Known-good answers, read from a TOML file keyed by day and part:
+///
+/// ```toml
+/// [day1]
+/// part1 = 1154
+/// part2 = 1127
+/// ```
+///
+/// Answers can be integers or strings (for multi-line answers, such as day
+/// 13's).
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct ExpectedAnswers(BTreeMap<(u8, Part), String>);
+
+impl ExpectedAnswers {
+    pub fn load(path: &str) -> Result<Self, Error> {
+        let answers =
+            std::fs::read_to_string(path).map_err(|e| format!("Could not read {}: {}", path, e))?;
+        answers
+            .parse()
+            .map_err(|e| format!("Invalid answers in {}: {}", path, e).into())
+    }
+
+    pub fn get(&self, day: u8, part: Part) -> Option<&str> {
+        self.0.get(&(day, part)).map(String::as_str)
+    }
+
+    /// Compare the answers of `solution` to the expected ones. Answers without
+    /// an expected value are not checked.
+    pub fn check(&self, solution: &Solution) -> Vec<Mismatch> {
+        solution
+            .answers
+            .iter()
+            .filter_map(|answer| {
+                let expected = self.get(solution.day, answer.part)?;
+                if same_answer(expected, &answer.value) {
+                    None
+                } else {
+                    Some(Mismatch {
+                        day: solution.day,
+                        part: answer.part,
+                        expected: expected.to_string(),
+                        actual: answer.value.clone(),
+                    })
+                }
+            })
+            .collect()
+    }
+}
+
+// Trailing whitespace is irrelevant, especially after multi-line answers.
+fn same_answer(expected: &str, actual: &str) -> bool {
+    expected.trim_end() == actual.trim_end()
+}
+
+impl FromStr for ExpectedAnswers {
+    type Err = String;
+
+    fn from_str(input: &str) -> Result<Self, Self::Err> {
+        let table: toml::Table = input.parse().map_err(|e: toml::de::Error| e.to_string())?;
+
+        let mut answers = BTreeMap::new();
+        for (day_key, parts) in table {
+            let day = day_key
+                .strip_prefix("day")
+                .and_then(|day| day.parse().ok())
+                .filter(|day| DAYS.contains(day))
+                .ok_or_else(|| format!("Invalid day: {}", day_key))?;
+            let parts = parts
+                .as_table()
+                .ok_or_else(|| format!("Expected a table for {}", day_key))?;
+
+            for (part_key, value) in parts {
+                let part = part_key
+                    .strip_prefix("part")
+                    .and_then(|part| part.parse::<Part>().ok())
+                    .filter(|part| Part::available(day).contains(part))
+                    .ok_or_else(|| format!("Invalid part: {}.{}", day_key, part_key))?;
+                let value = match value {
+                    toml::Value::Integer(n) => n.to_string(),
+                    toml::Value::String(s) => s.clone(),
+                    _ => {
+                        return Err(format!(
+                            "Expected an integer or a string for {}.{}",
+                            day_key, part_key
+                        ))
+                    }
+                };
+                answers.insert((day, part), value);
+            }
+        }
+
+        Ok(ExpectedAnswers(answers))
+    }
+}
+
+/// An answer that differs from the expected one.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Mismatch {
+    pub day: u8,
+    pub part: Part,
+    pub expected: String,
+    pub actual: String,
+}
+
+impl fmt::Display for Mismatch {
+    // Diff-style, line by line so that multi-line answers stay readable.
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        writeln!(f, "Day {}, part {}:", self.day, self.part)?;
+        for line in self.expected.trim_end().lines() {
+            writeln!(f, "- {}", line)?;
+        }
+        for line in self.actual.trim_end().lines() {
+            writeln!(f, "+ {}", line)?;
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::runner;
+
+    #[test]
+    fn parse_answers() {
+        let answers: ExpectedAnswers =
+            "[day1]\npart1 = 7\npart2 = \"5\"\n\n[day13]\npart2 = \"\"\"\n#.\n.#\n\"\"\"\n"
+                .parse()
+                .unwrap();
+        assert_eq!(answers.get(1, Part::One), Some("7"));
+        assert_eq!(answers.get(1, Part::Two), Some("5"));
+        assert_eq!(answers.get(13, Part::One), None);
+        assert_eq!(answers.get(13, Part::Two), Some("#.\n.#\n"));
+    }
+
+    #[test]
+    fn invalid_answers() {
+        assert!("[day26]\npart1 = 1".parse::<ExpectedAnswers>().is_err());
+        assert!("[day25]\npart2 = 1".parse::<ExpectedAnswers>().is_err());
+        assert!("[day1]\npart1 = 1.5".parse::<ExpectedAnswers>().is_err());
+    }
+
+    #[test]
+    fn check_solution() {
+        let answers: ExpectedAnswers = "[day1]\npart1 = 7\npart2 = 6".parse().unwrap();
+        let solution = runner::solve(
+            1,
+            "199\n200\n208\n210\n200\n207\n240\n269\n260\n263",
+            &[Part::One, Part::Two],
+        )
+        .unwrap();
+        assert_eq!(
+            answers.check(&solution),
+            [Mismatch {
+                day: 1,
+                part: Part::Two,
+                expected: String::from("6"),
+                actual: String::from("5"),
+            }]
+        );
+    }
+}
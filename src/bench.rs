@@ -0,0 +1,300 @@
+use crate::runner::{self, Error, Part};
+use serde::{Deserialize, Serialize};
+use std::alloc::{GlobalAlloc, Layout, System};
+use std::collections::HashMap;
+use std::convert::TryFrom;
+use std::fmt;
+use std::ops::Sub;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::time::Duration;
+
+static ALLOCATIONS: AtomicUsize = AtomicUsize::new(0);
+static ALLOCATED_BYTES: AtomicUsize = AtomicUsize::new(0);
+
+/// Allocator counting the allocations of the whole process, to be installed
+/// with `#[global_allocator]`.
+#[derive(Debug, Default, Clone, Copy)]
+pub struct CountingAllocator;
+
+impl CountingAllocator {
+    fn record(size: usize) {
+        ALLOCATIONS.fetch_add(1, Ordering::Relaxed);
+        ALLOCATED_BYTES.fetch_add(size, Ordering::Relaxed);
+    }
+}
+
+unsafe impl GlobalAlloc for CountingAllocator {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        Self::record(layout.size());
+        System.alloc(layout)
+    }
+
+    unsafe fn alloc_zeroed(&self, layout: Layout) -> *mut u8 {
+        Self::record(layout.size());
+        System.alloc_zeroed(layout)
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        System.dealloc(ptr, layout)
+    }
+
+    // Growing a vector is counted as an allocation of its new size.
+    unsafe fn realloc(&self, ptr: *mut u8, layout: Layout, new_size: usize) -> *mut u8 {
+        Self::record(new_size);
+        System.realloc(ptr, layout, new_size)
+    }
+}
+
+/// Number of allocations and allocated bytes, always zero unless
+/// `CountingAllocator` is the global allocator.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct Allocations {
+    pub count: usize,
+    pub bytes: usize,
+}
+
+impl Allocations {
+    /// The allocations since the start of the process.
+    pub fn now() -> Self {
+        Allocations {
+            count: ALLOCATIONS.load(Ordering::Relaxed),
+            bytes: ALLOCATED_BYTES.load(Ordering::Relaxed),
+        }
+    }
+}
+
+impl Sub for Allocations {
+    type Output = Allocations;
+
+    fn sub(self, other: Self) -> Self::Output {
+        Allocations {
+            count: self.count.wrapping_sub(other.count),
+            bytes: self.bytes.wrapping_sub(other.bytes),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(into = "String", try_from = "String")]
+pub enum Phase {
+    Parse,
+    Solve(Part),
+}
+
+impl fmt::Display for Phase {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Phase::Parse => write!(f, "parse"),
+            Phase::Solve(part) => write!(f, "part{}", part),
+        }
+    }
+}
+
+impl From<Phase> for String {
+    fn from(phase: Phase) -> Self {
+        phase.to_string()
+    }
+}
+
+impl TryFrom<String> for Phase {
+    type Error = String;
+
+    fn try_from(phase: String) -> Result<Self, Self::Error> {
+        match phase.strip_prefix("part") {
+            _ if phase == "parse" => Ok(Phase::Parse),
+            Some(part) => part.parse().map(Phase::Solve),
+            None => Err(format!("Invalid phase: {}", phase)),
+        }
+    }
+}
+
+/// Timings of a phase over several runs, with the allocations of a single run.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Measurement {
+    pub day: u8,
+    pub phase: Phase,
+    pub runs: usize,
+    pub min_ns: u64,
+    pub median_ns: u64,
+    pub p95_ns: u64,
+    pub allocations: usize,
+    pub allocated_bytes: usize,
+}
+
+impl Measurement {
+    fn new(day: u8, phase: Phase, mut times: Vec<Duration>, allocations: Allocations) -> Self {
+        times.sort_unstable();
+        let nanos = |idx: usize| times.get(idx).map_or(0, |t| t.as_nanos() as u64);
+        // Nearest-rank percentile.
+        let p95 = (times.len() * 95).div_ceil(100).saturating_sub(1);
+
+        Measurement {
+            day,
+            phase,
+            runs: times.len(),
+            min_ns: nanos(0),
+            median_ns: nanos(times.len() / 2),
+            p95_ns: nanos(p95),
+            allocations: allocations.count,
+            allocated_bytes: allocations.bytes,
+        }
+    }
+}
+
+/// Parse and solve the requested `parts` of `day` `runs` times, measuring the
+/// parsing and each part separately.
+pub fn bench(day: u8, input: &str, parts: &[Part], runs: usize) -> Result<Vec<Measurement>, Error> {
+    let mut parse_times = Vec::with_capacity(runs);
+    let mut solve_times = vec![Vec::with_capacity(runs); parts.len()];
+    let mut last = None;
+
+    for _ in 0..runs.max(1) {
+        let solution = runner::solve(day, input, parts)?;
+        parse_times.push(solution.parse_time);
+        for (times, answer) in solve_times.iter_mut().zip(&solution.answers) {
+            times.push(answer.solve_time);
+        }
+        last = Some(solution);
+    }
+
+    // The allocations hardly vary between runs, so keep the last ones.
+    let mut measurements = Vec::new();
+    if let Some(solution) = last {
+        measurements.push(Measurement::new(
+            day,
+            Phase::Parse,
+            parse_times,
+            solution.parse_allocations,
+        ));
+        for (times, answer) in solve_times.into_iter().zip(&solution.answers) {
+            measurements.push(Measurement::new(
+                day,
+                Phase::Solve(answer.part),
+                times,
+                answer.allocations,
+            ));
+        }
+    }
+
+    Ok(measurements)
+}
+
+/// Measurements of a previous run, saved as JSON lines.
+#[derive(Debug, Default, Clone)]
+pub struct Baseline(HashMap<(u8, Phase), Measurement>);
+
+impl Baseline {
+    pub fn load(path: &str) -> Result<Self, Error> {
+        let baseline =
+            std::fs::read_to_string(path).map_err(|e| format!("Could not read {}: {}", path, e))?;
+
+        let mut measurements = HashMap::new();
+        for (idx, line) in baseline.lines().enumerate() {
+            let m: Measurement = serde_json::from_str(line)
+                .map_err(|e| format!("Invalid baseline in {}, line {}: {}", path, idx + 1, e))?;
+            measurements.insert((m.day, m.phase), m);
+        }
+
+        Ok(Baseline(measurements))
+    }
+
+    pub fn save(measurements: &[Measurement], path: &str) -> Result<(), Error> {
+        let mut baseline = String::new();
+        for m in measurements {
+            baseline += &serde_json::to_string(m)?;
+            baseline.push('\n');
+        }
+
+        std::fs::write(path, baseline)
+            .map_err(|e| format!("Could not write {}: {}", path, e).into())
+    }
+
+    pub fn get(&self, day: u8, phase: Phase) -> Option<&Measurement> {
+        self.0.get(&(day, phase))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn measurement(day: u8, phase: Phase, millis: &[u64]) -> Measurement {
+        let times = millis.iter().map(|&ms| Duration::from_millis(ms)).collect();
+        let allocations = Allocations {
+            count: 3,
+            bytes: 120,
+        };
+        Measurement::new(day, phase, times, allocations)
+    }
+
+    #[test]
+    fn statistics() {
+        // 1 to 20 ms, out of order.
+        let millis: Vec<u64> = (1..=20).map(|ms| (ms * 7) % 20 + 1).collect();
+        let m = measurement(4, Phase::Parse, &millis);
+        assert_eq!(m.runs, 20);
+        assert_eq!(m.min_ns, 1_000_000);
+        assert_eq!(m.median_ns, 11_000_000);
+        // The 19th of 20 runs.
+        assert_eq!(m.p95_ns, 19_000_000);
+        assert_eq!((m.allocations, m.allocated_bytes), (3, 120));
+
+        let m = measurement(4, Phase::Parse, &[5]);
+        assert_eq!(
+            (m.min_ns, m.median_ns, m.p95_ns),
+            (5_000_000, 5_000_000, 5_000_000)
+        );
+        let m = measurement(4, Phase::Parse, &[]);
+        assert_eq!((m.runs, m.min_ns, m.p95_ns), (0, 0, 0));
+    }
+
+    #[test]
+    fn phases() {
+        for phase in [
+            Phase::Parse,
+            Phase::Solve(Part::One),
+            Phase::Solve(Part::Two),
+        ] {
+            assert_eq!(Phase::try_from(String::from(phase)), Ok(phase));
+        }
+        assert_eq!(String::from(Phase::Solve(Part::Two)), "part2");
+        assert_eq!(
+            Phase::try_from(String::from("part3")),
+            Err(String::from("Invalid part: 3"))
+        );
+        assert_eq!(
+            Phase::try_from(String::from("solve")),
+            Err(String::from("Invalid phase: solve"))
+        );
+    }
+
+    #[test]
+    fn baselines() {
+        let dir = std::env::temp_dir().join(format!("aoc-bench-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("baseline.jsonl");
+        let path = path.to_str().unwrap();
+
+        let measurements = [
+            measurement(1, Phase::Parse, &[2, 1, 3]),
+            measurement(1, Phase::Solve(Part::Two), &[8]),
+        ];
+        Baseline::save(&measurements, path).unwrap();
+        let baseline = Baseline::load(path).unwrap();
+        assert_eq!(baseline.get(1, Phase::Parse), Some(&measurements[0]));
+        assert_eq!(
+            baseline.get(1, Phase::Solve(Part::Two)),
+            Some(&measurements[1])
+        );
+        assert_eq!(baseline.get(1, Phase::Solve(Part::One)), None);
+        assert_eq!(baseline.get(2, Phase::Parse), None);
+
+        std::fs::write(path, "{}\n").unwrap();
+        let error = Baseline::load(path).unwrap_err().to_string();
+        assert!(error.starts_with(&format!("Invalid baseline in {}, line 1", path)));
+
+        std::fs::remove_dir_all(&dir).unwrap();
+        let error = Baseline::load(path).unwrap_err().to_string();
+        assert!(error.starts_with(&format!("Could not read {}", path)));
+    }
+}
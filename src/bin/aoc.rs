@@ -0,0 +1,601 @@
+use advent_of_code_2021::answers::{ExpectedAnswers, Mismatch};
+use advent_of_code_2021::bench::{self, Baseline, CountingAllocator, Measurement};
+use advent_of_code_2021::inputs::InputProvider;
+use advent_of_code_2021::runner::{self, Error, Part, Solution, DAYS};
+use advent_of_code_2021::ParseError;
+use serde::Serialize;
+use sha2::{Digest, Sha256};
+use std::io::Read;
+use std::panic::{self, AssertUnwindSafe};
+use std::path::PathBuf;
+use std::process::ExitCode;
+use std::time::Duration;
+
+#[global_allocator]
+static ALLOCATOR: CountingAllocator = CountingAllocator;
+
+const USAGE: &str = "\
+Usage: aoc (--day <DAY> | --all) [--part <PART>] [--input <PATH>] [--json]
+           [--check <PATH>] [--inputs <DIR>] [--user <NAME>]
+       aoc --bench (--day <DAY> | --all) [--part <PART>] [--input <PATH>]
+           [--json] [--runs <N>] [--baseline <PATH>] [--save-baseline <PATH>]
+
+Options:
+    --day <DAY>      Solve a single day (1 to 25).
+    --all            Solve every day in sequence.
+    --part <PART>    Solve part 1, part 2 or both (default: both).
+    --input <PATH>   Read the input from PATH, or from stdin if PATH is `-`,
+                     instead of `inputs/dayN.txt`. Only valid with --day.
+    --inputs <DIR>   Look for the inputs in DIR (default: `inputs`): as
+                     `<NAME>/dayN.txt` then `dayN.txt`, possibly gzip
+                     compressed, or in the `.tar`, `.tar.gz` and `.tgz`
+                     archives of DIR. Failing that, download them from
+                     $AOC_INPUT_URL with the $AOC_SESSION token, and cache
+                     them in $AOC_CACHE_DIR (default: DIR/.cache).
+    --user <NAME>    Use the inputs of NAME (default: $AOC_USER).
+    --json           Print one JSON object per answer (or error), with the
+                     answer type, the SHA-256 of the input and the parsing and
+                     solving times in nanoseconds. Parse errors also give the
+                     line, the column and the offending text.
+    --check <PATH>   Compare the answers to the expected ones in the TOML file
+                     PATH (e.g. `[day1]` then `part1 = 1154`), and report the
+                     differences. Fails if any answer differs.
+    --bench          Parse and solve repeatedly, then report the minimum,
+                     median and 95th percentile times, and the number of
+                     allocations, of the parsing and of each part.
+    --runs <N>       Number of runs of each day with --bench (default: 10).
+    --baseline <PATH>
+                     Compare the --bench results to those saved in PATH.
+    --save-baseline <PATH>
+                     Save the --bench results to PATH, as JSON lines.
+    -h, --help       Print this message.";
+
+#[derive(Debug, Clone)]
+enum Input {
+    Default,
+    Path(PathBuf),
+    Stdin,
+}
+
+impl Input {
+    fn read(&self, day: u8, provider: &InputProvider) -> Result<String, Error> {
+        let path = match self {
+            Input::Default => return provider.read(day),
+            Input::Path(path) => path.clone(),
+            Input::Stdin => {
+                let mut input = String::new();
+                std::io::stdin().read_to_string(&mut input)?;
+                return Ok(input);
+            }
+        };
+
+        std::fs::read_to_string(&path)
+            .map_err(|e| format!("Could not read {}: {}", path.display(), e).into())
+    }
+}
+
+#[derive(Debug, Clone)]
+struct Options {
+    days: Vec<u8>,
+    // `None` means both parts.
+    part: Option<Part>,
+    input: Input,
+    provider: InputProvider,
+    json: bool,
+    check: Option<String>,
+    bench: Option<BenchOptions>,
+}
+
+#[derive(Debug, Clone)]
+struct BenchOptions {
+    runs: usize,
+    baseline: Option<String>,
+    save_baseline: Option<String>,
+}
+
+impl Options {
+    // Returns `None` if the help message was requested.
+    fn from_args(mut args: impl Iterator<Item = String>) -> Result<Option<Self>, String> {
+        let mut day = None;
+        let mut all = false;
+        let mut part = None;
+        let mut input = Input::Default;
+        let mut inputs_dir = None;
+        let mut user = None;
+        let mut json = false;
+        let mut check = None;
+        let mut bench = false;
+        let mut runs = None;
+        let mut baseline = None;
+        let mut save_baseline = None;
+
+        while let Some(arg) = args.next() {
+            let mut value = || {
+                args.next()
+                    .ok_or_else(|| format!("Missing value for {}", arg))
+            };
+
+            match arg.as_str() {
+                "--day" => {
+                    let value = value()?;
+                    match value.parse() {
+                        Ok(d) if DAYS.contains(&d) => day = Some(d),
+                        _ => return Err(format!("Invalid day: {}", value)),
+                    }
+                }
+                "--all" => all = true,
+                "--part" => {
+                    part = match value()?.as_str() {
+                        "both" => None,
+                        p => Some(p.parse()?),
+                    }
+                }
+                "--input" => {
+                    input = match value()?.as_str() {
+                        "-" => Input::Stdin,
+                        path => Input::Path(PathBuf::from(path)),
+                    }
+                }
+                "--inputs" => inputs_dir = Some(value()?),
+                "--user" => user = Some(value()?),
+                "--json" => json = true,
+                "--check" => check = Some(value()?),
+                "--bench" => bench = true,
+                "--runs" => {
+                    let value = value()?;
+                    match value.parse() {
+                        Ok(n) if n > 0 => runs = Some(n),
+                        _ => return Err(format!("Invalid number of runs: {}", value)),
+                    }
+                }
+                "--baseline" => baseline = Some(value()?),
+                "--save-baseline" => save_baseline = Some(value()?),
+                "-h" | "--help" => return Ok(None),
+                _ => return Err(format!("Unknown argument: {}", arg)),
+            }
+        }
+
+        let days = match (day, all) {
+            (Some(day), false) => vec![day],
+            // Skip the days without the requested part, rather than failing
+            // on them.
+            (None, true) if matches!(input, Input::Default) => DAYS
+                .filter(|&day| part.is_none_or(|part| Part::available(day).contains(&part)))
+                .collect(),
+            (None, true) => return Err(String::from("--input is only valid with --day")),
+            _ => return Err(String::from("Expected exactly one of --day or --all")),
+        };
+
+        if !matches!(input, Input::Default) && (inputs_dir.is_some() || user.is_some()) {
+            return Err(String::from(
+                "--inputs and --user are not valid with --input",
+            ));
+        }
+        let mut provider = InputProvider::from_env(inputs_dir.as_deref().unwrap_or("inputs"));
+        if let Some(user) = user {
+            provider = provider.with_user(user);
+        }
+
+        let bench = if bench {
+            if check.is_some() {
+                return Err(String::from("--check is not valid with --bench"));
+            }
+            Some(BenchOptions {
+                runs: runs.unwrap_or(10),
+                baseline,
+                save_baseline,
+            })
+        } else if runs.is_some() || baseline.is_some() || save_baseline.is_some() {
+            return Err(String::from(
+                "--runs, --baseline and --save-baseline are only valid with --bench",
+            ));
+        } else {
+            None
+        };
+
+        Ok(Some(Options {
+            days,
+            part,
+            input,
+            provider,
+            json,
+            check,
+            bench,
+        }))
+    }
+}
+
+#[derive(Debug, Serialize)]
+struct AnswerRecord<'a> {
+    day: u8,
+    part: u8,
+    answer: &'a str,
+    #[serde(rename = "type")]
+    kind: &'a str,
+    input_sha256: &'a str,
+    parse_ns: u128,
+    solve_ns: u128,
+    // Only known with --check, and if there is an expected answer.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    correct: Option<bool>,
+}
+
+#[derive(Debug, Serialize)]
+struct ErrorRecord<'a> {
+    day: u8,
+    error: &'a str,
+    // Only known for parse errors.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    line: Option<usize>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    column: Option<usize>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    text: Option<&'a str>,
+}
+
+fn print_text(solution: &Solution) {
+    println!("Day {}", solution.day);
+    for answer in &solution.answers {
+        // Some answers (day 13) span multiple lines.
+        if answer.value.contains('\n') {
+            println!("Part {}:\n{}", answer.part, answer.value.trim_end());
+        } else {
+            println!("Part {}: {}", answer.part, answer.value);
+        }
+    }
+}
+
+fn print_json(
+    solution: &Solution,
+    input: &str,
+    mismatches: &[Mismatch],
+    expected: Option<&ExpectedAnswers>,
+) -> Result<(), Error> {
+    let input_sha256 = format!("{:x}", Sha256::digest(input.as_bytes()));
+    for answer in &solution.answers {
+        let correct = expected
+            .and_then(|e| e.get(solution.day, answer.part))
+            .map(|_| !mismatches.iter().any(|m| m.part == answer.part));
+        let record = AnswerRecord {
+            day: solution.day,
+            part: u8::from(answer.part),
+            answer: &answer.value,
+            kind: answer.kind,
+            input_sha256: &input_sha256,
+            parse_ns: solution.parse_time.as_nanos(),
+            solve_ns: answer.solve_time.as_nanos(),
+            correct,
+        };
+        println!("{}", serde_json::to_string(&record)?);
+    }
+
+    Ok(())
+}
+
+// Run `f`, turning a panic into an error so that it only fails its own day.
+fn catch_panic<T>(f: impl FnOnce() -> Result<T, Error>) -> Result<T, Error> {
+    panic::catch_unwind(AssertUnwindSafe(f)).unwrap_or_else(|payload| {
+        let message = payload
+            .downcast_ref::<&str>()
+            .map(|s| s.to_string())
+            .or_else(|| payload.downcast_ref::<String>().cloned())
+            .unwrap_or_else(|| String::from("Unknown error"));
+        Err(format!("Panicked: {}", message).into())
+    })
+}
+
+// Returns the answers differing from the `expected` ones.
+fn run(
+    day: u8,
+    options: &Options,
+    expected: Option<&ExpectedAnswers>,
+) -> Result<Vec<Mismatch>, Error> {
+    let input = options.input.read(day, &options.provider)?;
+    let solution = runner::solve(day, &input, &parts(day, options))?;
+    let mismatches = expected.map(|e| e.check(&solution)).unwrap_or_default();
+
+    if options.json {
+        print_json(&solution, &input, &mismatches, expected)?;
+    } else {
+        print_text(&solution);
+    }
+
+    Ok(mismatches)
+}
+
+fn parts(day: u8, options: &Options) -> Vec<Part> {
+    match options.part {
+        Some(part) => vec![part],
+        None => Part::available(day).to_vec(),
+    }
+}
+
+// Relative change, e.g. "+12.5%".
+fn change(new: u64, old: u64) -> String {
+    if old == 0 {
+        String::from("-")
+    } else {
+        format!("{:+.1}%", (new as f64 / old as f64 - 1.0) * 100.0)
+    }
+}
+
+fn print_measurement(
+    m: &Measurement,
+    baseline: Option<&Baseline>,
+    json: bool,
+) -> Result<(), Error> {
+    if json {
+        println!("{}", serde_json::to_string(m)?);
+        return Ok(());
+    }
+
+    let ns = |n: u64| format!("{:.1?}", Duration::from_nanos(n));
+    print!(
+        "{:>3}  {:<6}  {:>10}  {:>10}  {:>10}  {:>9}  {:>11}",
+        m.day,
+        m.phase,
+        ns(m.min_ns),
+        ns(m.median_ns),
+        ns(m.p95_ns),
+        m.allocations,
+        m.allocated_bytes
+    );
+    if let Some(old) = baseline.and_then(|b| b.get(m.day, m.phase)) {
+        print!(
+            "  {:>9}  {:>9}",
+            change(m.median_ns, old.median_ns),
+            change(m.allocations as u64, old.allocations as u64)
+        );
+    }
+    println!();
+
+    Ok(())
+}
+
+fn run_bench(
+    day: u8,
+    options: &Options,
+    bench: &BenchOptions,
+    baseline: Option<&Baseline>,
+) -> Result<Vec<Measurement>, Error> {
+    let input = options.input.read(day, &options.provider)?;
+    let measurements = bench::bench(day, &input, &parts(day, options), bench.runs)?;
+    for m in &measurements {
+        print_measurement(m, baseline, options.json)?;
+    }
+
+    Ok(measurements)
+}
+
+fn main_bench(options: &Options, bench: &BenchOptions) -> ExitCode {
+    let baseline = match bench.baseline.as_deref().map(Baseline::load) {
+        Some(Ok(baseline)) => Some(baseline),
+        Some(Err(e)) => {
+            eprintln!("{}", e);
+            return ExitCode::FAILURE;
+        }
+        None => None,
+    };
+
+    if !options.json {
+        print!(
+            "Day  Phase   {:>10}  {:>10}  {:>10}  {:>9}  {:>11}",
+            "Min", "Median", "P95", "Allocs", "Bytes"
+        );
+        if baseline.is_some() {
+            print!("  {:>9}  {:>9}", "Median Δ", "Allocs Δ");
+        }
+        println!();
+    }
+
+    let mut success = true;
+    let mut measurements = Vec::new();
+    for &day in &options.days {
+        match catch_panic(|| run_bench(day, options, bench, baseline.as_ref())) {
+            Ok(m) => measurements.extend(m),
+            Err(e) => {
+                print_error(day, &e, options.json);
+                success = false;
+            }
+        }
+    }
+
+    if let Some(path) = &bench.save_baseline {
+        if let Err(e) = Baseline::save(&measurements, path) {
+            eprintln!("{}", e);
+            success = false;
+        }
+    }
+
+    if success {
+        ExitCode::SUCCESS
+    } else {
+        ExitCode::FAILURE
+    }
+}
+
+fn print_error(day: u8, e: &Error, json: bool) {
+    let parse_error = e.downcast_ref::<ParseError>();
+    if json {
+        let error = match parse_error {
+            Some(p) => p.message().to_string(),
+            None => e.to_string(),
+        };
+        let record = ErrorRecord {
+            day,
+            error: &error,
+            line: parse_error.map(ParseError::line),
+            column: parse_error.map(ParseError::column),
+            text: parse_error.map(ParseError::text),
+        };
+        println!("{}", serde_json::to_string(&record).unwrap_or(error));
+    } else if parse_error.is_some() {
+        // Parse errors already mention the day.
+        eprintln!("{}", e);
+    } else {
+        eprintln!("Day {}: {}", day, e);
+    }
+}
+
+fn print_report(mismatches: &[Mismatch], path: &str) {
+    eprintln!("Answers differing from {}:", path);
+    for mismatch in mismatches {
+        eprint!("{}", mismatch);
+    }
+
+    let mut days: Vec<u8> = mismatches.iter().map(|m| m.day).collect();
+    days.dedup();
+    let days: Vec<String> = days.iter().map(u8::to_string).collect();
+    eprintln!(
+        "{} answer(s) differ, on day(s): {}.",
+        mismatches.len(),
+        days.join(", ")
+    );
+}
+
+fn main() -> ExitCode {
+    let options = match Options::from_args(std::env::args().skip(1)) {
+        Ok(Some(options)) => options,
+        Ok(None) => {
+            println!("{}", USAGE);
+            return ExitCode::SUCCESS;
+        }
+        Err(e) => {
+            eprintln!("{}\n\n{}", e, USAGE);
+            return ExitCode::from(2);
+        }
+    };
+
+    if let Some(bench) = &options.bench {
+        return main_bench(&options, bench);
+    }
+
+    let expected = match options.check.as_deref().map(ExpectedAnswers::load) {
+        Some(Ok(expected)) => Some(expected),
+        Some(Err(e)) => {
+            eprintln!("{}", e);
+            return ExitCode::FAILURE;
+        }
+        None => None,
+    };
+
+    // Keep going when a day fails, so that `--all` reports every error.
+    let mut success = true;
+    let mut mismatches = Vec::new();
+    for &day in &options.days {
+        match catch_panic(|| run(day, &options, expected.as_ref())) {
+            Ok(m) => mismatches.extend(m),
+            Err(e) => {
+                print_error(day, &e, options.json);
+                success = false;
+            }
+        }
+    }
+
+    if let (Some(path), false) = (&options.check, mismatches.is_empty()) {
+        print_report(&mismatches, path);
+        success = false;
+    }
+
+    if success {
+        ExitCode::SUCCESS
+    } else {
+        ExitCode::FAILURE
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn options(args: &str) -> Result<Option<Options>, String> {
+        Options::from_args(args.split_whitespace().map(String::from))
+    }
+
+    #[test]
+    fn day_and_part() {
+        let options = options("--day 3 --part 2 --json").unwrap().unwrap();
+        assert_eq!(options.days, [3]);
+        assert_eq!(options.part, Some(Part::Two));
+        assert!(options.json);
+        assert!(options.bench.is_none());
+
+        let options = self::options("--day 25 --part both").unwrap().unwrap();
+        assert_eq!((options.days, options.part), (vec![25], None));
+        assert!(self::options("--help").unwrap().is_none());
+    }
+
+    #[test]
+    fn all_days() {
+        let options = options("--all").unwrap().unwrap();
+        assert_eq!(options.days, DAYS.collect::<Vec<u8>>());
+
+        // There is no part 2 on the last day.
+        let options = self::options("--all --part 2").unwrap().unwrap();
+        assert_eq!(options.days, (1..=24).collect::<Vec<u8>>());
+        let options = self::options("--all --part 1").unwrap().unwrap();
+        assert_eq!(options.days.len(), 25);
+    }
+
+    #[test]
+    fn bench() {
+        let options = options("--bench --all --runs 3 --baseline base.jsonl")
+            .unwrap()
+            .unwrap();
+        let bench = options.bench.unwrap();
+        assert_eq!(bench.runs, 3);
+        assert_eq!(bench.baseline.as_deref(), Some("base.jsonl"));
+        assert_eq!(
+            self::options("--bench --day 1")
+                .unwrap()
+                .unwrap()
+                .bench
+                .unwrap()
+                .runs,
+            10
+        );
+    }
+
+    #[test]
+    fn invalid_options() {
+        let error = |args| options(args).unwrap_err();
+        assert_eq!(error("--day 26"), "Invalid day: 26");
+        assert_eq!(error("--day 1 --part 3"), "Invalid part: 3");
+        assert_eq!(
+            error("--day 1 --all"),
+            "Expected exactly one of --day or --all"
+        );
+        assert_eq!(error(""), "Expected exactly one of --day or --all");
+        assert_eq!(
+            error("--all --input x.txt"),
+            "--input is only valid with --day"
+        );
+        assert_eq!(error("--day"), "Missing value for --day");
+        assert_eq!(error("--day 1 --verbose"), "Unknown argument: --verbose");
+        assert_eq!(
+            error("--bench --day 1 --runs 0"),
+            "Invalid number of runs: 0"
+        );
+        assert_eq!(
+            error("--day 1 --runs 2"),
+            "--runs, --baseline and --save-baseline are only valid with --bench"
+        );
+        assert_eq!(
+            error("--bench --day 1 --check answers.toml"),
+            "--check is not valid with --bench"
+        );
+        assert_eq!(
+            error("--day 1 --input x.txt --user me"),
+            "--inputs and --user are not valid with --input"
+        );
+    }
+
+    #[test]
+    fn panics_fail_their_day() {
+        let error = catch_panic::<()>(|| panic!("No winning board")).unwrap_err();
+        assert_eq!(error.to_string(), "Panicked: No winning board");
+        let error = catch_panic::<()>(|| panic!("{} boards", 0)).unwrap_err();
+        assert_eq!(error.to_string(), "Panicked: 0 boards");
+        assert_eq!(catch_panic(|| Ok(1)).unwrap(), 1);
+    }
+}
@@ -0,0 +1,102 @@
+use advent_of_code_2021::day2::{self, Navigation, Position, Submarine, Trajectory};
+use advent_of_code_2021::runner::Error;
+use std::io::Read;
+use std::process::ExitCode;
+
+const USAGE: &str = "\
+Usage: course [--aim] [--svg] [<PATH>]
+
+Replay the submarine commands of day 2 read from PATH (or stdin), and print
+every state along the course as CSV, or the course as an SVG image. Notable
+events, such as surfacing or overflows, are reported on stderr.
+
+Besides `forward`, `down` and `up`, scripts can use `back n`, `hold n` (stay
+in place for n steps), `repeat n {` ... `}` blocks and `#` comments.
+
+Options:
+    --aim        Follow the commands with the aim, as in part 2.
+    --svg        Print an SVG image instead of CSV.
+    -h, --help   Print this message.";
+
+#[derive(Debug, Default, Clone)]
+struct Options {
+    aim: bool,
+    svg: bool,
+    path: Option<String>,
+}
+
+impl Options {
+    // Returns `None` if the help message was requested.
+    fn from_args(args: impl Iterator<Item = String>) -> Result<Option<Self>, String> {
+        let mut options = Options::default();
+        for arg in args {
+            match arg.as_str() {
+                "--aim" => options.aim = true,
+                "--svg" => options.svg = true,
+                "-h" | "--help" => return Ok(None),
+                _ if arg.starts_with("--") => return Err(format!("Unknown argument: {}", arg)),
+                _ if options.path.is_some() => return Err(format!("Unexpected argument: {}", arg)),
+                _ => options.path = Some(arg),
+            }
+        }
+
+        Ok(Some(options))
+    }
+}
+
+fn export<N: Navigation>(commands: &[day2::Command], svg: bool) {
+    let trajectory: Trajectory<N> = Trajectory::new(commands);
+    for event in &trajectory.events {
+        eprintln!("{}", event);
+    }
+
+    if svg {
+        print!("{}", trajectory.to_svg());
+    } else {
+        print!("{}", trajectory.to_csv());
+    }
+}
+
+fn run(options: &Options) -> Result<(), Error> {
+    let input = match options.path.as_deref() {
+        None | Some("-") => {
+            let mut input = String::new();
+            std::io::stdin().read_to_string(&mut input)?;
+            input
+        }
+        Some(path) => {
+            std::fs::read_to_string(path).map_err(|e| format!("Could not read {}: {}", path, e))?
+        }
+    };
+    let commands = day2::parse(&input)?;
+
+    if options.aim {
+        export::<Submarine>(&commands, options.svg);
+    } else {
+        export::<Position>(&commands, options.svg);
+    }
+
+    Ok(())
+}
+
+fn main() -> ExitCode {
+    let options = match Options::from_args(std::env::args().skip(1)) {
+        Ok(Some(options)) => options,
+        Ok(None) => {
+            println!("{}", USAGE);
+            return ExitCode::SUCCESS;
+        }
+        Err(e) => {
+            eprintln!("{}\n\n{}", e, USAGE);
+            return ExitCode::from(2);
+        }
+    };
+
+    match run(&options) {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(e) => {
+            eprintln!("{}", e);
+            ExitCode::FAILURE
+        }
+    }
+}
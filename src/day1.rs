@@ -0,0 +1,218 @@
+use crate::error::{parse_lines, parse_number};
+use crate::runner::Error;
+use crate::ParseError;
+use std::collections::VecDeque;
+use std::fmt;
+use std::io::{BufRead, BufReader, Read};
+
+/// How the sums of a sliding window over the depths evolve.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct DepthReport {
+    /// The number of (complete) windows.
+    pub windows: usize,
+    pub increases: usize,
+    pub decreases: usize,
+    pub plateaus: usize,
+    /// The longest runs of consecutive increases and decreases, counted in
+    /// changes from one window to the next.
+    pub longest_increase: usize,
+    pub longest_decrease: usize,
+}
+
+/// Error returned for a window of zero depths.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct EmptyWindow;
+
+impl fmt::Display for EmptyWindow {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "The window must hold at least one depth")
+    }
+}
+
+impl std::error::Error for EmptyWindow {}
+
+/// Analyses depths one at a time, only keeping the last `window` of them.
+#[derive(Debug, Clone)]
+pub struct DepthAnalyser {
+    window: usize,
+    depths: VecDeque<u32>,
+    sum: u64,
+    previous_sum: Option<u64>,
+    increase_run: usize,
+    decrease_run: usize,
+    report: DepthReport,
+}
+
+impl DepthAnalyser {
+    pub fn new(window: usize) -> Result<Self, EmptyWindow> {
+        if window == 0 {
+            return Err(EmptyWindow);
+        }
+
+        Ok(DepthAnalyser {
+            window,
+            depths: VecDeque::with_capacity(window + 1),
+            sum: 0,
+            previous_sum: None,
+            increase_run: 0,
+            decrease_run: 0,
+            report: DepthReport::default(),
+        })
+    }
+
+    pub fn push(&mut self, depth: u32) {
+        self.depths.push_back(depth);
+        self.sum += u64::from(depth);
+        if self.depths.len() > self.window {
+            self.sum -= self.depths.pop_front().map_or(0, u64::from);
+        }
+        if self.depths.len() < self.window {
+            return;
+        }
+
+        self.report.windows += 1;
+        let (increase_run, decrease_run) = match self.previous_sum {
+            Some(previous) if self.sum > previous => {
+                self.report.increases += 1;
+                (self.increase_run + 1, 0)
+            }
+            Some(previous) if self.sum < previous => {
+                self.report.decreases += 1;
+                (0, self.decrease_run + 1)
+            }
+            Some(_) => {
+                self.report.plateaus += 1;
+                (0, 0)
+            }
+            None => (0, 0),
+        };
+        self.increase_run = increase_run;
+        self.decrease_run = decrease_run;
+        self.report.longest_increase = self.report.longest_increase.max(increase_run);
+        self.report.longest_decrease = self.report.longest_decrease.max(decrease_run);
+        self.previous_sum = Some(self.sum);
+    }
+
+    pub fn report(&self) -> DepthReport {
+        self.report
+    }
+}
+
+impl Extend<u32> for DepthAnalyser {
+    fn extend<T: IntoIterator<Item = u32>>(&mut self, depths: T) {
+        for depth in depths {
+            self.push(depth);
+        }
+    }
+}
+
+pub fn analyse(
+    depths: impl IntoIterator<Item = u32>,
+    window: usize,
+) -> Result<DepthReport, EmptyWindow> {
+    let mut analyser = DepthAnalyser::new(window)?;
+    analyser.extend(depths);
+    Ok(analyser.report())
+}
+
+/// Analyse the depths read from `reader`, one per line, without holding more
+/// than a line and a window in memory.
+pub fn analyse_reader(reader: impl Read, window: usize) -> Result<DepthReport, Error> {
+    let mut analyser = DepthAnalyser::new(window)?;
+    for (idx, line) in BufReader::new(reader).lines().enumerate() {
+        let line = line?;
+        let depth = parse_number(&line, &line).map_err(|e| e.offset_lines(idx).on_day(1))?;
+        analyser.push(depth);
+    }
+
+    Ok(analyser.report())
+}
+
+pub fn parse(input: &str) -> Result<Vec<u32>, ParseError> {
+    parse_lines(input, |line| parse_number(line, line)).map_err(|e| e.on_day(1))
+}
+
+pub fn part1(depths: &[u32]) -> Result<usize, EmptyWindow> {
+    // How many measurements are larger than the previous measurement?
+    Ok(analyse(depths.iter().copied(), 1)?.increases)
+}
+
+pub fn part2(depths: &[u32]) -> Result<usize, EmptyWindow> {
+    // Consider sums of a three-measurement sliding window. How many sums are
+    // larger than the previous sum?
+    Ok(analyse(depths.iter().copied(), 3)?.increases)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const EXAMPLE: &str = "\
+199
+200
+208
+210
+200
+207
+240
+269
+260
+263";
+
+    #[test]
+    fn part1_example() {
+        assert_eq!(part1(&parse(EXAMPLE).unwrap()), Ok(7));
+    }
+
+    #[test]
+    fn part2_example() {
+        assert_eq!(part2(&parse(EXAMPLE).unwrap()), Ok(5));
+    }
+
+    #[test]
+    fn analyse_windows() {
+        let depths = parse(EXAMPLE).unwrap();
+        assert_eq!(
+            analyse(depths.iter().copied(), 1),
+            Ok(DepthReport {
+                windows: 10,
+                increases: 7,
+                decreases: 2,
+                plateaus: 0,
+                longest_increase: 3,
+                longest_decrease: 1,
+            })
+        );
+        // The sums are 607, 618, 618, 617, 647, 716, 769 and 792.
+        assert_eq!(
+            analyse(depths.iter().copied(), 3),
+            Ok(DepthReport {
+                windows: 8,
+                increases: 5,
+                decreases: 1,
+                plateaus: 1,
+                longest_increase: 4,
+                longest_decrease: 1,
+            })
+        );
+        assert_eq!(
+            analyse(depths.iter().copied(), 11),
+            Ok(DepthReport::default())
+        );
+        assert_eq!(analyse(depths, 0), Err(EmptyWindow));
+    }
+
+    #[test]
+    fn analyse_stream() {
+        let report = analyse_reader(EXAMPLE.as_bytes(), 3).unwrap();
+        assert_eq!(report.increases, 5);
+
+        let error = analyse_reader("1\n2\nthree\n".as_bytes(), 1).unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            "Day 1, line 3, column 1: Invalid number: \"three\""
+        );
+        let error = analyse_reader(EXAMPLE.as_bytes(), 0).unwrap_err();
+        assert_eq!(error.to_string(), "The window must hold at least one depth");
+    }
+}
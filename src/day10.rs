@@ -0,0 +1,638 @@
+use crate::error::{char_at, parse_lines, parse_number};
+use crate::ParseError;
+use std::fmt;
+use std::str::FromStr;
+
+/// A kind of bracket: the index of its pair in the `Brackets`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct BracketSubType(pub usize);
+
+impl BracketSubType {
+    // The brackets of the puzzle, in the default `Brackets`.
+    pub const PARENTHESIS: BracketSubType = BracketSubType(0);
+    pub const SQUARE_BRACKET: BracketSubType = BracketSubType(1);
+    pub const CURLY_BRACKET: BracketSubType = BracketSubType(2);
+    pub const ANGLE_BRACKET: BracketSubType = BracketSubType(3);
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BracketType {
+    Opening(BracketSubType),
+    Closing(BracketSubType),
+}
+
+impl BracketType {
+    pub fn sub_type(self) -> BracketSubType {
+        match self {
+            BracketType::Opening(sub_type) | BracketType::Closing(sub_type) => sub_type,
+        }
+    }
+}
+
+/// Opening and closing delimiters, with their scores.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BracketPair {
+    pub open: String,
+    pub close: String,
+    pub syntax_error_score: u64,
+    pub completion_score: u64,
+}
+
+/// The brackets of a language, and how to score completions: the puzzle's
+/// by default.
+///
+/// They can be read from a pair per line, with the opening and closing
+/// delimiters followed by the syntax error and completion scores, and an
+/// optional line giving the base of the completion scores:
+///
+/// ```text
+/// ( ) 3 1
+/// begin end 100 10
+/// base 11
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Brackets {
+    pairs: Vec<BracketPair>,
+    completion_base: u64,
+}
+
+impl Brackets {
+    /// No brackets, with the completion scores in base 5.
+    pub fn empty() -> Self {
+        Brackets {
+            pairs: Vec::new(),
+            completion_base: 5,
+        }
+    }
+
+    /// Each closing bracket of a completion multiplies the score so far by
+    /// `base` before adding its own score.
+    pub fn with_completion_base(mut self, base: u64) -> Self {
+        self.completion_base = base;
+        self
+    }
+
+    pub fn completion_base(&self) -> u64 {
+        self.completion_base
+    }
+
+    /// Returns `None` if a delimiter is empty, contains whitespace or is
+    /// already used.
+    pub fn with_pair(
+        mut self,
+        open: &str,
+        close: &str,
+        syntax_error_score: u64,
+        completion_score: u64,
+    ) -> Option<Self> {
+        let used = |d: &str| {
+            d.is_empty()
+                || d.contains(char::is_whitespace)
+                || self
+                    .pairs
+                    .iter()
+                    .any(|pair| pair.open == d || pair.close == d)
+        };
+        if used(open) || used(close) || open == close {
+            return None;
+        }
+
+        self.pairs.push(BracketPair {
+            open: open.to_string(),
+            close: close.to_string(),
+            syntax_error_score,
+            completion_score,
+        });
+        Some(self)
+    }
+
+    pub fn len(&self) -> usize {
+        self.pairs.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.pairs.is_empty()
+    }
+
+    /// Panics if the bracket is not one of these.
+    pub fn pair(&self, sub_type: BracketSubType) -> &BracketPair {
+        &self.pairs[sub_type.0]
+    }
+
+    pub fn syntax_error_score(&self, sub_type: BracketSubType) -> u64 {
+        self.pair(sub_type).syntax_error_score
+    }
+
+    pub fn completion_score(&self, sub_type: BracketSubType) -> u64 {
+        self.pair(sub_type).completion_score
+    }
+
+    /// The score of the completion closing the `unclosed` opening brackets,
+    /// or `None` on overflow.
+    pub fn score_completion(&self, unclosed: &[BracketSubType]) -> Option<u64> {
+        unclosed.iter().rev().try_fold(0u64, |acc, &s| {
+            acc.checked_mul(self.completion_base)?
+                .checked_add(self.completion_score(s))
+        })
+    }
+
+    pub fn delimiter(&self, bracket: BracketType) -> &str {
+        match bracket {
+            BracketType::Opening(sub_type) => &self.pair(sub_type).open,
+            BracketType::Closing(sub_type) => &self.pair(sub_type).close,
+        }
+    }
+
+    // Delimiters of several characters are separated by spaces when writing
+    // lines.
+    fn separator(&self) -> &str {
+        let single = |d: &String| d.chars().count() == 1;
+        if self
+            .pairs
+            .iter()
+            .all(|pair| single(&pair.open) && single(&pair.close))
+        {
+            ""
+        } else {
+            " "
+        }
+    }
+
+    /// Split a line into brackets, the longest delimiter first, skipping
+    /// whitespace.
+    pub fn tokenize(&self, line: &str) -> Result<Vec<BracketType>, ParseError> {
+        Ok(self
+            .locate(line)?
+            .into_iter()
+            .map(|(_, bracket)| bracket)
+            .collect())
+    }
+
+    /// Like `tokenize`, with the column where each bracket starts in the
+    /// line, counting characters from 0.
+    pub fn locate(&self, line: &str) -> Result<Vec<(usize, BracketType)>, ParseError> {
+        let mut brackets = Vec::new();
+        let mut rest = line.trim_start();
+        while !rest.is_empty() {
+            let bracket = self
+                .pairs
+                .iter()
+                .enumerate()
+                .flat_map(|(idx, pair)| {
+                    [
+                        (&pair.open, BracketType::Opening(BracketSubType(idx))),
+                        (&pair.close, BracketType::Closing(BracketSubType(idx))),
+                    ]
+                })
+                .filter(|(delimiter, _)| rest.starts_with(delimiter.as_str()))
+                .max_by_key(|(delimiter, _)| delimiter.len());
+
+            match bracket {
+                Some((delimiter, bracket)) => {
+                    let column = line[..line.len() - rest.len()].chars().count();
+                    brackets.push((column, bracket));
+                    rest = rest[delimiter.len()..].trim_start();
+                }
+                None => return Err(ParseError::new("Invalid bracket", line, char_at(rest, 0))),
+            }
+        }
+
+        Ok(brackets)
+    }
+
+    /// A line of brackets per line of the input.
+    pub fn parse(&self, input: &str) -> Result<Vec<Vec<BracketType>>, ParseError> {
+        parse_lines(input, |line| self.tokenize(line))
+    }
+
+    /// Write a line with these delimiters.
+    pub fn text(&self, line: &[BracketType]) -> String {
+        line.iter()
+            .map(|&b| self.delimiter(b))
+            .collect::<Vec<_>>()
+            .join(self.separator())
+    }
+
+    pub fn message(&self, diagnostic: &Diagnostic) -> String {
+        let found = self.delimiter(BracketType::Closing(diagnostic.found));
+        match diagnostic.expected {
+            Some(expected) => {
+                let expected = self.delimiter(BracketType::Closing(expected));
+                format!("Expected {}, found {}", expected, found)
+            }
+            None => format!("Unexpected {}", found),
+        }
+    }
+}
+
+impl Default for Brackets {
+    fn default() -> Self {
+        [
+            ("(", ")", 3, 1),
+            ("[", "]", 57, 2),
+            ("{", "}", 1197, 3),
+            ("<", ">", 25137, 4),
+        ]
+        .into_iter()
+        .try_fold(
+            Brackets::empty(),
+            |brackets, (open, close, syntax, completion)| {
+                brackets.with_pair(open, close, syntax, completion)
+            },
+        )
+        .expect("Invalid default brackets")
+    }
+}
+
+// Add the pair, or set the base, on a line of the configuration.
+fn parse_pair(brackets: Brackets, line: &str) -> Result<Brackets, ParseError> {
+    let fields: Vec<&str> = line.split_whitespace().collect();
+    if let ["base", base] = fields[..] {
+        return Ok(brackets.with_completion_base(parse_number(line, base)?));
+    }
+    let [open, close, syntax, completion] = fields[..] else {
+        let message = format!("Expected 4 fields, found {}", fields.len());
+        return Err(ParseError::new(message, line, line.trim()));
+    };
+    let (syntax, completion) = (parse_number(line, syntax)?, parse_number(line, completion)?);
+
+    brackets
+        .with_pair(open, close, syntax, completion)
+        .ok_or_else(|| ParseError::new("Repeated delimiter", line, line.trim()))
+}
+
+impl FromStr for Brackets {
+    type Err = ParseError;
+
+    fn from_str(input: &str) -> Result<Self, Self::Err> {
+        let mut brackets = Brackets::empty();
+        for (idx, line) in input.lines().enumerate() {
+            if !line.trim().is_empty() {
+                brackets = parse_pair(brackets, line).map_err(|e| e.offset_lines(idx))?;
+            }
+        }
+
+        Ok(brackets)
+    }
+}
+
+/// How to fix a closing bracket that does not match.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Repair {
+    /// Replace it with this bracket.
+    Substitute(BracketType),
+    /// Insert this bracket before it.
+    Insert(BracketType),
+}
+
+/// A closing bracket that does not match the last opening bracket.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Diagnostic {
+    /// The index of the bracket in the line.
+    pub index: usize,
+    pub found: BracketSubType,
+    /// `None` if there is no opening bracket to close.
+    pub expected: Option<BracketSubType>,
+    pub repair: Repair,
+}
+
+/// Every error of a line, and how to fix and complete it.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Report {
+    pub diagnostics: Vec<Diagnostic>,
+    /// The opening brackets left without closing brackets, once repaired.
+    pub unclosed: Vec<BracketSubType>,
+    /// The line with the repairs, but not the completion.
+    pub repaired: Vec<BracketType>,
+}
+
+impl Report {
+    pub fn is_corrupted(&self) -> bool {
+        !self.diagnostics.is_empty()
+    }
+
+    /// The closing brackets completing the line.
+    pub fn completion(&self) -> Vec<BracketType> {
+        self.unclosed
+            .iter()
+            .rev()
+            .map(|&s| BracketType::Closing(s))
+            .collect()
+    }
+
+    /// The repaired and completed line.
+    pub fn fixed(&self) -> Vec<BracketType> {
+        let mut fixed = self.repaired.clone();
+        fixed.extend(self.completion());
+        fixed
+    }
+
+    /// The `source` line the report is about, as written, with a caret
+    /// under each error, followed by its message.
+    pub fn render(&self, source: &str, brackets: &Brackets) -> Result<String, ParseError> {
+        let columns: Vec<usize> = brackets
+            .locate(source)?
+            .into_iter()
+            .map(|(column, _)| column)
+            .collect();
+
+        let mut rendered = format!("{}\n", source);
+        for diagnostic in &self.diagnostics {
+            // Past the end of the line if it is not the one checked.
+            let column = columns
+                .get(diagnostic.index)
+                .copied()
+                .unwrap_or_else(|| source.chars().count());
+            let message = brackets.message(diagnostic);
+            rendered += &format!("{:>1$} {2}\n", "^", column + 1, message);
+        }
+        Ok(rendered)
+    }
+}
+
+/// Check a whole line, recovering from each error. A closing bracket that
+/// does not match is taken as the end of the bracket before if it matches,
+/// and substituted otherwise.
+pub fn check(line: &[BracketType]) -> Report {
+    let mut stack = Vec::new();
+    let mut diagnostics = Vec::new();
+    let mut repaired = Vec::with_capacity(line.len());
+
+    for (index, &b) in line.iter().enumerate() {
+        let found = match b {
+            BracketType::Opening(subtype) => {
+                stack.push(subtype);
+                repaired.push(b);
+                continue;
+            }
+            BracketType::Closing(subtype) => subtype,
+        };
+
+        let (expected, repair) = match stack.last().copied() {
+            Some(last) if last == found => {
+                stack.pop();
+                repaired.push(b);
+                continue;
+            }
+            Some(last) if stack.len() > 1 && stack[stack.len() - 2] == found => {
+                stack.truncate(stack.len() - 2);
+                repaired.extend([BracketType::Closing(last), b]);
+                (Some(last), Repair::Insert(BracketType::Closing(last)))
+            }
+            Some(last) => {
+                stack.pop();
+                repaired.push(BracketType::Closing(last));
+                (Some(last), Repair::Substitute(BracketType::Closing(last)))
+            }
+            None => {
+                repaired.extend([BracketType::Opening(found), b]);
+                (None, Repair::Insert(BracketType::Opening(found)))
+            }
+        };
+
+        diagnostics.push(Diagnostic {
+            index,
+            found,
+            expected,
+            repair,
+        });
+    }
+
+    Report {
+        diagnostics,
+        unclosed: stack,
+        repaired,
+    }
+}
+
+/// The first closing bracket that does not match, or the opening brackets
+/// without closing brackets.
+pub fn balanced_brackets(line: &[BracketType]) -> Result<Vec<BracketSubType>, BracketSubType> {
+    let report = check(line);
+    match report.diagnostics.first() {
+        Some(diagnostic) => Err(diagnostic.found),
+        None => Ok(report.unclosed),
+    }
+}
+
+pub fn parse(input: &str) -> Result<Vec<Vec<BracketType>>, ParseError> {
+    if input.trim().is_empty() {
+        return Err(ParseError::missing("Missing lines", input).on_day(10));
+    }
+
+    Brackets::default().parse(input).map_err(|e| e.on_day(10))
+}
+
+pub fn part1(code: &[Vec<BracketType>]) -> u64 {
+    // Find the first illegal character in each corrupted line of the navigation
+    // subsystem. What is the total syntax error score for those errors?
+    let brackets = Brackets::default();
+    code.iter()
+        .filter_map(|line| balanced_brackets(line).err())
+        .map(|subtype| brackets.syntax_error_score(subtype))
+        .sum()
+}
+
+/// Why the middle completion score cannot be found.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CompletionError {
+    /// The completion score of the line (numbered from 1) overflows.
+    Overflow(usize),
+    /// All the lines are corrupted.
+    NoIncompleteLines,
+}
+
+impl fmt::Display for CompletionError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            CompletionError::Overflow(line) => {
+                write!(f, "Line {}: completion score overflow", line)
+            }
+            CompletionError::NoIncompleteLines => write!(f, "No incomplete lines"),
+        }
+    }
+}
+
+impl std::error::Error for CompletionError {}
+
+pub fn part2(code: &[Vec<BracketType>]) -> Result<u64, CompletionError> {
+    // Find the completion string for each incomplete line, score the completion
+    // strings, and sort the scores. What is the middle score?
+    let brackets = Brackets::default();
+    let mut completion_scores: Vec<u64> = code
+        .iter()
+        .enumerate()
+        .filter_map(|(idx, line)| Some((idx, balanced_brackets(line).ok()?)))
+        .map(|(idx, unclosed)| {
+            brackets
+                .score_completion(&unclosed)
+                .ok_or(CompletionError::Overflow(idx + 1))
+        })
+        .collect::<Result<_, _>>()?;
+    if completion_scores.is_empty() {
+        return Err(CompletionError::NoIncompleteLines);
+    }
+    completion_scores.sort_unstable();
+    Ok(completion_scores[completion_scores.len() / 2])
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const EXAMPLE: &str = "\
+[({(<(())[]>[[{[]{<()<>>
+[(()[<>])]({[<{<<[]>>(
+{([(<{}[<>[]}>{[]{[(<()>
+(((({<>}<{<{<>}{[]{[]{}
+[[<[([]))<([[{}[[()]]]
+[{[{({}]{}}([{[{{{}}([]
+{<[[]]>}<{[{[{[]{()[[[]
+[<(<(<(<{}))><([]([]()
+<{([([[(<>()){}]>(<<{{
+<{([{{}}[<[[[<>{}]]]>[]]";
+
+    #[test]
+    fn part1_example() {
+        assert_eq!(part1(&parse(EXAMPLE).unwrap()), 26397);
+    }
+
+    #[test]
+    fn part2_example() {
+        assert_eq!(part2(&parse(EXAMPLE).unwrap()), Ok(288957));
+    }
+
+    #[test]
+    fn no_middle_score() {
+        let code = parse(&format!("()\n{}", "<".repeat(30))).unwrap();
+        assert_eq!(part2(&code), Err(CompletionError::Overflow(2)));
+        assert_eq!(
+            part2(&parse("(]").unwrap()),
+            Err(CompletionError::NoIncompleteLines)
+        );
+    }
+
+    #[test]
+    fn corrupted_line() {
+        let line = parse("{([(<{}[<>[]}>{[]{[(<()>").unwrap().remove(0);
+        assert_eq!(balanced_brackets(&line), Err(BracketSubType::CURLY_BRACKET));
+    }
+
+    fn text(line: &[BracketType]) -> String {
+        Brackets::default().text(line)
+    }
+
+    fn checked(line: &str) -> (&str, Report) {
+        (line, check(&parse(line).unwrap().remove(0)))
+    }
+
+    #[test]
+    fn diagnostics() {
+        let (line, report) = checked("{([(<{}[<>[]}>{[]{[(<()>");
+        assert_eq!(
+            report.diagnostics[0],
+            Diagnostic {
+                index: 12,
+                found: BracketSubType::CURLY_BRACKET,
+                expected: Some(BracketSubType::SQUARE_BRACKET),
+                repair: Repair::Substitute(BracketType::Closing(BracketSubType::SQUARE_BRACKET)),
+            }
+        );
+        assert_eq!(
+            report.render(line, &Brackets::default()).unwrap(),
+            "\
+{([(<{}[<>[]}>{[]{[(<()>
+            ^ Expected ], found }
+"
+        );
+        assert_eq!(text(&report.repaired), "{([(<{}[<>[]]>{[]{[(<()>");
+        assert_eq!(text(&report.fixed()), "{([(<{}[<>[]]>{[]{[(<()>)]}})])}");
+        assert!(check(&report.fixed()).unclosed.is_empty());
+
+        // Scanning goes on after an error.
+        let (line, report) = checked("(]<)>");
+        assert_eq!(
+            report.render(line, &Brackets::default()).unwrap(),
+            "\
+(]<)>
+ ^ Expected ), found ]
+   ^ Expected >, found )
+    ^ Unexpected >
+"
+        );
+        assert_eq!(text(&report.fixed()), "()<><>");
+    }
+
+    #[test]
+    fn repairs() {
+        // A missing closing bracket is inserted.
+        let (_, report) = checked("[(]");
+        assert_eq!(
+            report.diagnostics[0].repair,
+            Repair::Insert(BracketType::Closing(BracketSubType::PARENTHESIS))
+        );
+        assert_eq!(text(&report.fixed()), "[()]");
+
+        // So is a missing opening bracket.
+        let (_, report) = checked(">[]");
+        assert_eq!(report.diagnostics[0].expected, None);
+        assert_eq!(
+            Brackets::default().message(&report.diagnostics[0]),
+            "Unexpected >"
+        );
+        assert_eq!(text(&report.fixed()), "<>[]");
+
+        let (_, report) = checked("[({<");
+        assert!(!report.is_corrupted());
+        assert_eq!(text(&report.completion()), ">})]");
+    }
+
+    #[test]
+    fn custom_brackets() {
+        let brackets: Brackets = "( ) 3 1\n\u{ab} \u{bb} 10 5\nbegin end 100 10\n"
+            .parse()
+            .unwrap();
+        let source = "begin (\u{ab}\u{bb})  begin ) end";
+        let line = brackets.tokenize(source).unwrap();
+        assert_eq!(line.len(), 8);
+        assert_eq!(line[0], BracketType::Opening(BracketSubType(2)));
+
+        let report = check(&line);
+        assert_eq!(report.diagnostics[0].index, 6);
+        assert_eq!(
+            report.render(source, &brackets).unwrap(),
+            "\
+begin (\u{ab}\u{bb})  begin ) end
+                  ^ Expected end, found )
+"
+        );
+        assert_eq!(brackets.syntax_error_score(report.diagnostics[0].found), 3);
+        assert_eq!(
+            brackets.text(&report.repaired),
+            "begin ( \u{ab} \u{bb} ) begin end end"
+        );
+        assert_eq!(brackets.completion_score(BracketSubType(1)), 5);
+
+        // Completing `begin ( «` scores 5, then 5 * 5 + 1, then 26 * 5 + 10.
+        let line = brackets.tokenize("begin ( \u{ab}").unwrap();
+        let unclosed = balanced_brackets(&line).unwrap();
+        assert_eq!(brackets.score_completion(&unclosed), Some(140));
+        let brackets: Brackets = "( ) 3 1\nbegin end 100 10\nbase 11".parse().unwrap();
+        assert_eq!(brackets.completion_base(), 11);
+        let line = brackets.tokenize("begin (").unwrap();
+        let unclosed = balanced_brackets(&line).unwrap();
+        assert_eq!(brackets.score_completion(&unclosed), Some(21));
+
+        let error = brackets.tokenize("begin x").unwrap_err();
+        assert_eq!((error.column(), error.text()), (7, "x"));
+        let error = "( ) 3 1\n[ ) 1 2".parse::<Brackets>().unwrap_err();
+        assert_eq!((error.line(), error.message()), (2, "Repeated delimiter"));
+        let error = "( ) 3".parse::<Brackets>().unwrap_err();
+        assert_eq!(error.message(), "Expected 4 fields, found 3");
+    }
+
+    #[test]
+    fn missing_lines() {
+        assert_eq!(parse("").unwrap_err().message(), "Missing lines");
+    }
+}
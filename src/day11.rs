@@ -0,0 +1,300 @@
+use crate::grid::{Grid, Position};
+use crate::ParseError;
+use std::collections::VecDeque;
+use std::str::FromStr;
+
+/// An octopus flashing, because of the octopus next to it that flashed before
+/// or, when `cause` is `None`, because of the step itself.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Flash {
+    pub position: Position,
+    pub cause: Option<Position>,
+}
+
+/// The flashes of a step, in cascade order: those caused by the step first,
+/// in reading order, then those they caused, and so on.
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct StepReport {
+    pub flashes: Vec<Flash>,
+}
+
+/// The states after steps `start` and `start + period` are the same, so the
+/// octopuses repeat the same steps forever.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Cycle {
+    pub start: usize,
+    pub period: usize,
+}
+
+/// The first step when all the octopuses flash, and the number of steps
+/// until they all flash again.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Synchronisation {
+    pub step: usize,
+    pub period: usize,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct DumboOctopusGrid(Grid<u8>);
+
+impl DumboOctopusGrid {
+    pub fn width(&self) -> usize {
+        self.0.width()
+    }
+
+    pub fn height(&self) -> usize {
+        self.0.height()
+    }
+
+    pub fn len(&self) -> usize {
+        self.0.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+
+    /// The octopuses that flashed during the last step.
+    pub fn nbr_flashes(&self) -> usize {
+        self.0.iter().filter(|o| **o == 0).count()
+    }
+
+    pub fn step(&mut self) -> StepReport {
+        // Increment the energy level. Grab those that will initially flash at
+        // the same time.
+        for o in self.0.iter_mut() {
+            *o += 1;
+        }
+        let mut flashers: VecDeque<Flash> = self
+            .0
+            .enumerate()
+            .filter(|(_, o)| **o > 9)
+            .map(|(position, _)| Flash {
+                position,
+                cause: None,
+            })
+            .collect();
+
+        // An octopus flashes when its energy goes above 9, which only happens
+        // once.
+        let mut flashes = Vec::new();
+        while let Some(flash) = flashers.pop_front() {
+            let targets: Vec<Position> = self.0.neighbours8(flash.position).collect();
+            for target in targets {
+                self.0[target] += 1;
+                if self.0[target] == 10 {
+                    flashers.push_back(Flash {
+                        position: target,
+                        cause: Some(flash.position),
+                    });
+                }
+            }
+            flashes.push(flash);
+        }
+
+        for o in self.0.iter_mut() {
+            if *o > 9 {
+                *o = 0;
+            }
+        }
+
+        StepReport { flashes }
+    }
+
+    /// The first state seen twice within `max_steps` steps.
+    pub fn find_cycle(&self, max_steps: usize) -> Option<Cycle> {
+        // Brent's algorithm: the tortoise waits for the hare at each power of
+        // two, until the hare comes back to it, which gives the period.
+        let mut tortoise = self.clone();
+        let mut hare = self.clone();
+        hare.step();
+        let (mut power, mut period) = (1, 1);
+        // The hare then took at most 3 times as many steps as the cycle ends
+        // after.
+        let mut steps = 1;
+        while tortoise != hare {
+            if steps >= max_steps.saturating_mul(3) {
+                return None;
+            }
+            if power == period {
+                tortoise = hare.clone();
+                power *= 2;
+                period = 0;
+            }
+            hare.step();
+            period += 1;
+            steps += 1;
+        }
+
+        // Two grids `period` steps apart first meet at the start of the cycle.
+        let mut tortoise = self.clone();
+        let mut hare = self.clone();
+        for _ in 0..period {
+            hare.step();
+        }
+        let mut start = 0;
+        while tortoise != hare {
+            tortoise.step();
+            hare.step();
+            start += 1;
+        }
+
+        (start + period <= max_steps).then_some(Cycle { start, period })
+    }
+
+    /// Returns `None` if the octopuses do not all flash within `max_steps`
+    /// steps, or never do because they come back to a state seen before.
+    pub fn synchronisation(&self, max_steps: usize) -> Option<Synchronisation> {
+        // The grid goes through every state, as the hare of Brent's algorithm
+        // (see `find_cycle`): when it meets the tortoise, it has seen them all.
+        let mut grid = self.clone();
+        let mut tortoise = self.clone();
+        let (mut power, mut length) = (1, 1);
+        let mut step = 1;
+        while grid.step().flashes.len() != grid.len() {
+            if step == max_steps || grid == tortoise {
+                return None;
+            }
+            if power == length {
+                tortoise = grid.clone();
+                power *= 2;
+                length = 0;
+            }
+            length += 1;
+            step += 1;
+        }
+        // All the energy levels are then 0, so they will all flash again
+        // within 10 steps.
+        let period = (1..)
+            .find(|_| grid.step().flashes.len() == grid.len())
+            .unwrap();
+
+        Some(Synchronisation { step, period })
+    }
+}
+
+impl Iterator for DumboOctopusGrid {
+    type Item = StepReport;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        Some(self.step())
+    }
+}
+
+impl FromStr for DumboOctopusGrid {
+    type Err = ParseError;
+
+    fn from_str(input: &str) -> Result<Self, Self::Err> {
+        let grid = Grid::parse(input, |c| c.to_digit(10).map(|d| d as u8))?;
+        if grid.is_empty() {
+            return Err(ParseError::missing("Missing octopuses", input));
+        }
+
+        Ok(DumboOctopusGrid(grid))
+    }
+}
+
+pub fn parse(input: &str) -> Result<DumboOctopusGrid, ParseError> {
+    input.parse().map_err(|e: ParseError| e.on_day(11))
+}
+
+pub fn part1(grid: &DumboOctopusGrid) -> usize {
+    // Given the starting energy levels of the dumbo octopuses in your cavern,
+    // simulate 100 steps. How many total flashes are there after 100 steps?
+    grid.clone()
+        .take(100)
+        .map(|report| report.flashes.len())
+        .sum()
+}
+
+pub fn part2(grid: &DumboOctopusGrid) -> usize {
+    // If you can calculate the exact moments when the octopuses will all flash
+    // simultaneously, you should be able to navigate through the cavern. What
+    // is the first step during which all octopuses flash?
+    grid.synchronisation(usize::MAX)
+        .expect("The octopuses repeat the same steps without ever flashing simultaneously.")
+        .step
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const EXAMPLE: &str = "\
+5483143223
+2745854711
+5264556173
+6141336146
+6357385478
+4167524645
+2176841721
+6882881134
+4846848554
+5283751526";
+
+    #[test]
+    fn part1_example() {
+        assert_eq!(part1(&parse(EXAMPLE).unwrap()), 1656);
+    }
+
+    #[test]
+    fn part2_example() {
+        assert_eq!(part2(&parse(EXAMPLE).unwrap()), 195);
+    }
+
+    #[test]
+    fn cascade() {
+        let mut grid: DumboOctopusGrid = "11111\n19991\n19191\n19991\n11111".parse().unwrap();
+        let flashes = grid.step().flashes;
+        assert_eq!(flashes.len(), 9);
+        assert!(flashes[..8].iter().all(|flash| flash.cause.is_none()));
+        assert_eq!(
+            flashes[8],
+            Flash {
+                position: Position::new(2, 2),
+                cause: Some(Position::new(3, 3)),
+            }
+        );
+        assert_eq!(grid.nbr_flashes(), 9);
+        assert_eq!(grid.step().flashes, []);
+    }
+
+    #[test]
+    fn synchronisation() {
+        let grid = parse(EXAMPLE).unwrap();
+        assert_eq!(
+            grid.synchronisation(1000),
+            Some(Synchronisation {
+                step: 195,
+                period: 10,
+            })
+        );
+        assert_eq!(grid.synchronisation(100), None);
+        assert_eq!(
+            grid.find_cycle(1000),
+            Some(Cycle {
+                start: 195,
+                period: 10,
+            })
+        );
+        // The state after step 195 comes back after step 205.
+        assert!(grid.find_cycle(205).is_some());
+        assert_eq!(grid.find_cycle(204), None);
+
+        // Any size of grid.
+        let grid: DumboOctopusGrid = "9999999\n8888888".parse().unwrap();
+        assert_eq!((grid.width(), grid.height()), (7, 2));
+        assert_eq!(grid.synchronisation(100).map(|s| s.step), Some(1));
+    }
+
+    #[test]
+    fn no_synchronisation() {
+        let grid: DumboOctopusGrid = "548".parse().unwrap();
+        assert_eq!(grid.synchronisation(usize::MAX), None);
+        assert!(grid.find_cycle(usize::MAX).is_some());
+
+        let rows: Vec<&str> = EXAMPLE.lines().take(3).collect();
+        let grid: DumboOctopusGrid = rows.join("\n").parse().unwrap();
+        assert_eq!(grid.synchronisation(usize::MAX), None);
+    }
+}
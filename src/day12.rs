@@ -0,0 +1,345 @@
+use crate::error::parse_lines;
+use crate::ParseError;
+use std::collections::HashMap;
+use std::str::FromStr;
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum Cave {
+    Small(String),
+    Large(String),
+}
+
+impl Cave {
+    pub fn is_small(&self) -> bool {
+        matches!(self, Cave::Small(_))
+    }
+}
+
+impl FromStr for Cave {
+    type Err = ParseError;
+
+    fn from_str(cave: &str) -> Result<Self, Self::Err> {
+        if !cave.is_empty() && cave.chars().all(|c| c.is_ascii_lowercase()) {
+            Ok(Cave::Small(cave.to_string()))
+        } else if !cave.is_empty() && cave.chars().all(|c| c.is_ascii_uppercase()) {
+            Ok(Cave::Large(cave.to_string()))
+        } else {
+            Err(ParseError::new("Invalid cave", cave, cave))
+        }
+    }
+}
+
+// Sets of small caves, a bit per cave.
+type SmallCaves = u128;
+
+/// At most this many small caves, so that their sets stay small.
+pub const MAX_SMALL_CAVES: usize = SmallCaves::BITS as usize;
+
+#[derive(Debug, Clone)]
+pub struct CaveSystem {
+    caves: Vec<Cave>,
+    indices: HashMap<Cave, usize>,
+    connections: Vec<Vec<usize>>,
+    // The bit of each small cave.
+    bits: Vec<SmallCaves>,
+}
+
+impl CaveSystem {
+    const START_CAVE: &'static str = "start";
+    const END_CAVE: &'static str = "end";
+
+    fn index(&self, name: &str) -> Option<usize> {
+        self.indices.get(&Cave::Small(name.to_string())).copied()
+    }
+
+    /// The number of paths from start to end, visiting small caves at most
+    /// once, except for a single one visited twice if `revisit` is allowed.
+    /// The start cave is never visited twice.
+    pub fn count_paths(&self, revisit: bool) -> u64 {
+        match (self.index(Self::START_CAVE), self.index(Self::END_CAVE)) {
+            (Some(start), Some(end)) => {
+                let mut memo = HashMap::new();
+                self.count_from(start, self.bits[start], revisit, (start, end), &mut memo)
+            }
+            _ => 0,
+        }
+    }
+
+    // The number of ways to the end from `cave`, which only depends on the
+    // small caves visited so far and whether one can still be revisited.
+    fn count_from(
+        &self,
+        cave: usize,
+        visited: SmallCaves,
+        revisit: bool,
+        (start, end): (usize, usize),
+        memo: &mut HashMap<(usize, SmallCaves, bool), u64>,
+    ) -> u64 {
+        if cave == end {
+            return 1;
+        }
+        if let Some(&count) = memo.get(&(cave, visited, revisit)) {
+            return count;
+        }
+
+        let mut count = 0;
+        for &next in &self.connections[cave] {
+            let bit = self.bits[next];
+            if next == start {
+                continue;
+            } else if visited & bit == 0 {
+                count += self.count_from(next, visited | bit, revisit, (start, end), memo);
+            } else if revisit {
+                count += self.count_from(next, visited, false, (start, end), memo);
+            }
+        }
+
+        memo.insert((cave, visited, revisit), count);
+        count
+    }
+
+    /// The paths counted by `count_paths`, one at a time.
+    pub fn paths(&self, revisit: bool) -> Paths<'_> {
+        let ends = self.index(Self::START_CAVE).zip(self.index(Self::END_CAVE));
+        Paths {
+            system: self,
+            frames: ends
+                .map(|(start, _)| {
+                    vec![Frame {
+                        cave: start,
+                        next: 0,
+                        revisit: false,
+                    }]
+                })
+                .unwrap_or_default(),
+            visited: ends.map_or(0, |(start, _)| self.bits[start]),
+            revisit,
+            ends: ends.unwrap_or_default(),
+        }
+    }
+}
+
+// A cave on the current path, with the index of the next connection to
+// follow from it.
+#[derive(Debug, Clone, Copy)]
+struct Frame {
+    cave: usize,
+    next: usize,
+    // Whether this is the small cave visited twice.
+    revisit: bool,
+}
+
+/// A depth-first search through the cave system, yielding paths as they
+/// reach the end.
+#[derive(Debug, Clone)]
+pub struct Paths<'a> {
+    system: &'a CaveSystem,
+    frames: Vec<Frame>,
+    visited: SmallCaves,
+    // Whether a small cave can still be visited twice.
+    revisit: bool,
+    ends: (usize, usize),
+}
+
+impl<'a> Iterator for Paths<'a> {
+    type Item = Vec<&'a Cave>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let system = self.system;
+        let (start, end) = self.ends;
+
+        while let Some(frame) = self.frames.last_mut() {
+            let Some(&next) = system.connections[frame.cave].get(frame.next) else {
+                // Go back, forgetting the visit.
+                let frame = self.frames.pop()?;
+                if frame.revisit {
+                    self.revisit = true;
+                } else {
+                    self.visited &= !system.bits[frame.cave];
+                }
+                continue;
+            };
+            frame.next += 1;
+
+            let bit = system.bits[next];
+            if next == start {
+                continue;
+            } else if next == end {
+                let mut path: Vec<&Cave> = self
+                    .frames
+                    .iter()
+                    .map(|frame| &system.caves[frame.cave])
+                    .collect();
+                path.push(&system.caves[end]);
+                return Some(path);
+            } else if self.visited & bit == 0 {
+                self.visited |= bit;
+                self.frames.push(Frame {
+                    cave: next,
+                    next: 0,
+                    revisit: false,
+                });
+            } else if self.revisit {
+                self.revisit = false;
+                self.frames.push(Frame {
+                    cave: next,
+                    next: 0,
+                    revisit: true,
+                });
+            }
+        }
+
+        None
+    }
+}
+
+impl FromStr for CaveSystem {
+    type Err = ParseError;
+
+    fn from_str(system: &str) -> Result<Self, Self::Err> {
+        let connections = parse_lines(system, |line| {
+            let (left, right) = line
+                .split_once('-')
+                .ok_or_else(|| ParseError::new("Invalid connection", line, line))?;
+            let left: Cave = left
+                .parse()
+                .map_err(|_| ParseError::new("Invalid cave", line, left))?;
+            let right: Cave = right
+                .parse()
+                .map_err(|_| ParseError::new("Invalid cave", line, right))?;
+            // There would be infinitely many paths going back and forth.
+            if !left.is_small() && !right.is_small() {
+                return Err(ParseError::new("Connected large caves", line, line));
+            }
+            Ok((left, right))
+        })?;
+
+        let mut cave_system = CaveSystem {
+            caves: Vec::new(),
+            indices: HashMap::new(),
+            connections: Vec::new(),
+            bits: Vec::new(),
+        };
+        let mut small_caves = 0;
+        for (idy, (left, right)) in connections.into_iter().enumerate() {
+            let mut index = |cave: Cave| -> Result<usize, ParseError> {
+                if let Some(&idx) = cave_system.indices.get(&cave) {
+                    return Ok(idx);
+                }
+                let bit = if !cave.is_small() {
+                    0
+                } else if small_caves == MAX_SMALL_CAVES {
+                    let line = system.lines().nth(idy).unwrap_or_default();
+                    let message = format!("Expected at most {} small caves", MAX_SMALL_CAVES);
+                    return Err(ParseError::new(message, line, line).offset_lines(idy));
+                } else {
+                    small_caves += 1;
+                    1 << (small_caves - 1)
+                };
+
+                let idx = cave_system.caves.len();
+                cave_system.caves.push(cave.clone());
+                cave_system.indices.insert(cave, idx);
+                cave_system.connections.push(Vec::new());
+                cave_system.bits.push(bit);
+                Ok(idx)
+            };
+            let (left, right) = (index(left)?, index(right)?);
+            cave_system.connections[left].push(right);
+            cave_system.connections[right].push(left);
+        }
+
+        Ok(cave_system)
+    }
+}
+
+pub fn parse(input: &str) -> Result<CaveSystem, ParseError> {
+    input.parse().map_err(|e: ParseError| e.on_day(12))
+}
+
+pub fn part1(cave_system: &CaveSystem) -> usize {
+    // How many paths through this cave system are there that visit small caves
+    // at most once?
+    cave_system.count_paths(false) as usize
+}
+
+pub fn part2(cave_system: &CaveSystem) -> usize {
+    // Given these new rules, how many paths through this cave system are there?
+    cave_system.count_paths(true) as usize
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashSet;
+
+    const EXAMPLE: &str = "\
+start-A
+start-b
+A-c
+A-b
+b-d
+A-end
+b-end";
+
+    #[test]
+    fn part1_example() {
+        assert_eq!(part1(&parse(EXAMPLE).unwrap()), 10);
+    }
+
+    #[test]
+    fn part2_example() {
+        assert_eq!(part2(&parse(EXAMPLE).unwrap()), 36);
+    }
+
+    const LARGER_EXAMPLE: &str = "\
+dc-end
+HN-start
+start-kj
+dc-start
+dc-HN
+LN-dc
+HN-end
+kj-sa
+kj-HN
+kj-dc";
+
+    #[test]
+    fn count_paths() {
+        let cave_system = parse(LARGER_EXAMPLE).unwrap();
+        assert_eq!(cave_system.count_paths(false), 19);
+        assert_eq!(cave_system.count_paths(true), 103);
+        assert_eq!(parse("a-b").unwrap().count_paths(false), 0);
+    }
+
+    #[test]
+    fn lazy_paths() {
+        let cave_system = parse(EXAMPLE).unwrap();
+        let name = |cave: &Cave| match cave {
+            Cave::Small(name) | Cave::Large(name) => name.clone(),
+        };
+        let paths: Vec<String> = cave_system
+            .paths(false)
+            .map(|path| path.into_iter().map(name).collect::<Vec<_>>().join(","))
+            .collect();
+        assert_eq!(paths.len(), 10);
+        assert_eq!(paths[0], "start,A,c,A,b,A,end");
+        assert!(paths.contains(&String::from("start,b,end")));
+
+        // All different.
+        let paths: HashSet<Vec<&Cave>> = cave_system.paths(true).collect();
+        assert_eq!(paths.len(), 36);
+
+        let cave_system = parse(LARGER_EXAMPLE).unwrap();
+        assert_eq!(cave_system.paths(true).count(), 103);
+    }
+
+    #[test]
+    fn invalid_cave_system() {
+        let error = parse("start-A\nA-B").unwrap_err();
+        assert_eq!(
+            (error.line(), error.message()),
+            (2, "Connected large caves")
+        );
+    }
+}
@@ -0,0 +1,214 @@
+use crate::error::{parse_lines, parse_number, split_blank_line};
+use crate::grid::{Grid, Position};
+use crate::ParseError;
+use std::cmp::Ordering;
+use std::collections::HashSet;
+use std::fmt;
+use std::str::FromStr;
+
+const DOT: char = '#';
+const EMPTY: char = '.';
+
+// Parse the `x,y` position of a dot.
+fn parse_dot(line: &str) -> Result<Position, ParseError> {
+    let (x, y) = line
+        .split_once(',')
+        .ok_or_else(|| ParseError::new("Invalid dot", line, line))?;
+
+    Ok(Position::new(
+        parse_number(line, x)?,
+        parse_number(line, y)?,
+    ))
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Fold {
+    Horizontal(usize),
+    Vertical(usize),
+}
+
+impl FromStr for Fold {
+    type Err = ParseError;
+
+    fn from_str(input: &str) -> Result<Self, Self::Err> {
+        let (d, location) = input
+            .strip_prefix("fold along ")
+            .and_then(|i| i.split_once('='))
+            .ok_or_else(|| ParseError::new("Invalid fold instruction", input, input))?;
+        let location = parse_number(input, location)?;
+        match d {
+            "x" => Ok(Fold::Vertical(location)),
+            "y" => Ok(Fold::Horizontal(location)),
+            _ => Err(ParseError::new("Invalid fold axis", input, d)),
+        }
+    }
+}
+
+// The coordinate `c` once folded along `location`: dots on the fold line
+// disappear, as do those beyond the mirror image of the paper.
+fn fold_coordinate(c: usize, location: usize) -> Option<usize> {
+    match c.cmp(&location) {
+        Ordering::Less => Some(c),
+        Ordering::Equal => None,
+        Ordering::Greater => location.checked_sub(c - location),
+    }
+}
+
+/// The dots are only laid out on a grid when shown, since the paper can be
+/// much larger than the number of dots before it is folded.
+#[derive(Debug, Clone, Default)]
+pub struct TransparentPaper {
+    dots: HashSet<Position>,
+    width: usize,
+    height: usize,
+}
+
+impl TransparentPaper {
+    pub fn nbr_dots(&self) -> usize {
+        self.dots.len()
+    }
+
+    pub fn apply_fold(&mut self, fold: Fold) -> &Self {
+        match fold {
+            Fold::Horizontal(location) => self.horizontal_fold(location),
+            Fold::Vertical(location) => self.vertical_fold(location),
+        }
+    }
+
+    pub fn horizontal_fold(&mut self, location: usize) -> &Self {
+        self.dots = self
+            .dots
+            .iter()
+            .filter_map(|p| Some(Position::new(p.x, fold_coordinate(p.y, location)?)))
+            .collect();
+        self.height = location.min(self.height);
+
+        self
+    }
+
+    pub fn vertical_fold(&mut self, location: usize) -> &Self {
+        self.dots = self
+            .dots
+            .iter()
+            .filter_map(|p| Some(Position::new(fold_coordinate(p.x, location)?, p.y)))
+            .collect();
+        self.width = location.min(self.width);
+
+        self
+    }
+}
+
+impl fmt::Display for TransparentPaper {
+    fn fmt(&self, f: &mut fmt::Formatter) -> Result<(), fmt::Error> {
+        let mut grid = Grid::new(self.width, self.height, EMPTY);
+        for &p in &self.dots {
+            grid[p] = DOT;
+        }
+        write!(f, "{}", grid)
+    }
+}
+
+impl<T: AsRef<[Position]>> From<T> for TransparentPaper {
+    fn from(dots: T) -> Self {
+        let dots = dots.as_ref();
+        TransparentPaper {
+            width: dots.iter().map(|p| p.x).max().unwrap_or_default() + 1,
+            height: dots.iter().map(|p| p.y).max().unwrap_or_default() + 1,
+            dots: dots.iter().copied().collect(),
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct Manual {
+    paper: TransparentPaper,
+    instructions: Vec<Fold>,
+}
+
+pub fn parse(input: &str) -> Result<Manual, ParseError> {
+    let (dots, instructions, offset) = split_blank_line(input)
+        .ok_or_else(|| ParseError::missing("Missing fold instructions", input).on_day(13))?;
+
+    let dots: Vec<Position> = parse_lines(dots, parse_dot).map_err(|e| e.on_day(13))?;
+    let instructions: Vec<Fold> =
+        parse_lines(instructions, str::parse).map_err(|e| e.offset_lines(offset).on_day(13))?;
+    let paper = TransparentPaper::from(&dots);
+
+    Ok(Manual {
+        paper,
+        instructions,
+    })
+}
+
+pub fn part1(manual: &Manual) -> usize {
+    // How many dots are visible after completing just the first fold
+    // instruction on your transparent paper?
+    let mut paper = manual.paper.clone();
+    paper.apply_fold(manual.instructions[0]).nbr_dots()
+}
+
+pub fn part2(manual: &Manual) -> String {
+    // What code do you use to activate the infrared thermal imaging camera
+    // system?
+    let mut paper = manual.paper.clone();
+    for &instruction in &manual.instructions {
+        paper.apply_fold(instruction);
+    }
+    paper.to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const EXAMPLE: &str = "\
+6,10
+0,14
+9,10
+0,3
+10,4
+4,11
+6,0
+6,12
+4,1
+0,13
+10,12
+3,4
+3,0
+8,4
+1,10
+2,14
+8,10
+9,0
+
+fold along y=7
+fold along x=5";
+
+    #[test]
+    fn part1_example() {
+        assert_eq!(part1(&parse(EXAMPLE).unwrap()), 17);
+    }
+
+    #[test]
+    fn part2_example() {
+        assert_eq!(
+            part2(&parse(EXAMPLE).unwrap()),
+            "#####\n#...#\n#...#\n#...#\n#####\n.....\n.....\n"
+        );
+    }
+
+    #[test]
+    fn crlf_and_large_papers() {
+        let crlf = EXAMPLE.replace('\n', "\r\n");
+        assert_eq!(part1(&parse(&crlf).unwrap()), 17);
+        let error = parse(&crlf.replace("x=5", "z=5")).unwrap_err();
+        assert_eq!((error.line(), error.message()), (21, "Invalid fold axis"));
+
+        // Far too large to lay out before folding.
+        let manual =
+            parse("0,3\n2000000000,0\n\nfold along x=1000000000\nfold along x=1\nfold along y=2")
+                .unwrap();
+        assert_eq!(part1(&manual), 2);
+        assert_eq!(part2(&manual), "#\n#\n");
+    }
+}
@@ -0,0 +1,174 @@
+use crate::error::{char_at, parse_lines};
+use crate::ParseError;
+use counter::Counter;
+use std::collections::HashMap;
+use std::convert::TryFrom;
+use std::str::FromStr;
+
+type Element = u8;
+type Pair = (Element, Element);
+
+#[derive(Debug, Clone)]
+pub struct Polymer {
+    elements: Counter<Element>,
+    pairs: Counter<Pair>,
+}
+
+impl Polymer {
+    pub fn strengthen(&mut self, rules: &PairInsertionRules) {
+        // We need to replace the pairs entirely: for example, CH yields CB and
+        // BH, so they are no longer CH pairs. Much simpler to generate a new
+        // counter.
+        // On the other hand, we can keep the former element counter, only
+        // updating with the new insertions (the C and H elements do not
+        // disappear).
+        let mut pairs = Counter::new();
+        for (pair, count) in self.pairs.iter() {
+            // Pairs without a matching rule are left as they are.
+            if let Some(&insertion) = rules.0.get(pair) {
+                *pairs.entry((pair.0, insertion)).or_default() += count;
+                *pairs.entry((insertion, pair.1)).or_default() += count;
+                *self.elements.entry(insertion).or_default() += count;
+            } else {
+                *pairs.entry(*pair).or_default() += count;
+            }
+        }
+
+        self.pairs = pairs;
+    }
+}
+
+impl<T: AsRef<[u8]>> From<T> for Polymer {
+    fn from(polymer: T) -> Self {
+        let polymer = polymer.as_ref();
+        let elements = polymer.iter().copied().collect();
+        let pairs = polymer
+            .iter()
+            .copied()
+            .zip(polymer.iter().skip(1).copied())
+            .collect();
+
+        Polymer { elements, pairs }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct PairInsertionRules(HashMap<Pair, Element>);
+
+fn parse_elements<const N: usize>(line: &str, text: &str) -> Result<[Element; N], ParseError> {
+    if let Some(idx) = text.find(|c: char| !c.is_ascii_uppercase()) {
+        return Err(ParseError::new("Invalid element", line, char_at(text, idx)));
+    }
+    <[Element; N]>::try_from(text.as_bytes()).map_err(|_| {
+        let message = format!("Expected {} elements, found {}", N, text.len());
+        ParseError::new(message, line, text)
+    })
+}
+
+impl FromStr for PairInsertionRules {
+    type Err = ParseError;
+
+    fn from_str(rules: &str) -> Result<Self, Self::Err> {
+        let rules = parse_lines(rules, |line| {
+            let (left, right) = line
+                .split_once(" -> ")
+                .ok_or_else(|| ParseError::new("Invalid pair insertion rule", line, line))?;
+            let [a, b] = parse_elements(line, left)?;
+            let [insertion] = parse_elements(line, right)?;
+            Ok(((a, b), insertion))
+        })?;
+
+        Ok(PairInsertionRules(rules.into_iter().collect()))
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct Polymerization {
+    template: Polymer,
+    rules: PairInsertionRules,
+}
+
+impl Polymerization {
+    // Apply `steps` steps of pair insertion, then take the quantity of the most
+    // common element and subtract the quantity of the least common element.
+    fn common_elements_difference(&self, steps: usize) -> usize {
+        let mut polymer = self.template.clone();
+        for _ in 0..steps {
+            polymer.strengthen(&self.rules);
+        }
+
+        polymer.elements.values().max().unwrap() - polymer.elements.values().min().unwrap()
+    }
+}
+
+pub fn parse(input: &str) -> Result<Polymerization, ParseError> {
+    let (template, rules) = input
+        .split_once("\n\n")
+        .ok_or_else(|| ParseError::missing("Missing pair insertion rules", input).on_day(14))?;
+    if template.is_empty() {
+        return Err(ParseError::missing("Missing polymer template", template).on_day(14));
+    }
+    if let Some(idx) = template.find(|c: char| !c.is_ascii_uppercase()) {
+        let element = char_at(template, idx);
+        return Err(ParseError::new("Invalid element", template, element).on_day(14));
+    }
+
+    let template = Polymer::from(template);
+    // The rules start after the template and a blank line.
+    let rules: PairInsertionRules = rules
+        .parse()
+        .map_err(|e: ParseError| e.offset_lines(2).on_day(14))?;
+
+    Ok(Polymerization { template, rules })
+}
+
+pub fn part1(polymerization: &Polymerization) -> usize {
+    // Apply 10 steps of pair insertion to the polymer template and find the
+    // most and least common elements in the result. What do you get if you
+    // take the quantity of the most common element and subtract the quantity
+    // of the least common element?
+    polymerization.common_elements_difference(10)
+}
+
+pub fn part2(polymerization: &Polymerization) -> usize {
+    // Apply 40 steps of pair insertion to the polymer template and find the
+    // most and least common elements in the result. What do you get if you
+    // take the quantity of the most common element and subtract the quantity
+    // of the least common element?
+    polymerization.common_elements_difference(40)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const EXAMPLE: &str = "\
+NNCB
+
+CH -> B
+HH -> N
+CB -> H
+NH -> C
+HB -> C
+HC -> B
+HN -> C
+NN -> C
+BH -> H
+NC -> B
+NB -> B
+BN -> B
+BB -> N
+BC -> B
+CC -> N
+CN -> C";
+
+    #[test]
+    fn part1_example() {
+        assert_eq!(part1(&parse(EXAMPLE).unwrap()), 1588);
+    }
+
+    #[test]
+    fn part2_example() {
+        assert_eq!(part2(&parse(EXAMPLE).unwrap()), 2188189693529);
+    }
+}
@@ -0,0 +1,135 @@
+use crate::grid::{Grid, Position};
+use crate::search::{self, Path};
+use crate::ParseError;
+use std::str::FromStr;
+
+#[derive(Debug, Clone)]
+pub struct Cave(Grid<u32>);
+
+impl Cave {
+    const MIN_RISK: u32 = 1;
+    const MAX_RISK: u32 = 9;
+
+    pub fn get(&self, p: Position) -> Option<&u32> {
+        self.0.get(p)
+    }
+
+    pub fn start_position(&self) -> Position {
+        Position::new(0, 0)
+    }
+
+    pub fn end_position(&self) -> Position {
+        Position::new(self.0.width() - 1, self.0.height() - 1)
+    }
+
+    pub fn lowest_risk_path(&self, start: Position, end: Position) -> Option<Path<Position, u32>> {
+        // Every step costs at least 1, as parsing rejects lower risk levels,
+        // so the Manhattan distance to the end never overestimates the
+        // remaining risk.
+        search::astar(
+            start,
+            |&p| self.0.neighbours4(p).map(|new_p| (new_p, self.0[new_p])),
+            |p| (p.x.abs_diff(end.x) + p.y.abs_diff(end.y)) as u32,
+            |&p| p == end,
+        )
+    }
+
+    pub fn expand(&self) -> Self {
+        let (width, height) = (self.0.width(), self.0.height());
+        Cave(Grid::from_fn(5 * width, 5 * height, |p| {
+            let level = self.0[Position::new(p.x % width, p.y % height)];
+            let new_level = level + (p.x / width + p.y / height) as u32;
+            if new_level > Self::MAX_RISK {
+                new_level - Self::MAX_RISK
+            } else {
+                new_level
+            }
+        }))
+    }
+}
+
+impl FromStr for Cave {
+    type Err = ParseError;
+
+    fn from_str(map: &str) -> Result<Self, Self::Err> {
+        let grid = Grid::parse(map, |c| {
+            c.to_digit(10)
+                .filter(|risk| (Cave::MIN_RISK..=Cave::MAX_RISK).contains(risk))
+        })?;
+        if grid.is_empty() {
+            return Err(ParseError::missing("Missing risk levels", map));
+        }
+
+        Ok(Cave(grid))
+    }
+}
+
+pub fn parse(input: &str) -> Result<Cave, ParseError> {
+    input.parse().map_err(|e: ParseError| e.on_day(15))
+}
+
+pub fn part1(risk_levels: &Cave) -> u32 {
+    // What is the lowest total risk of any path from the top left to the
+    // bottom right?
+    risk_levels
+        .lowest_risk_path(risk_levels.start_position(), risk_levels.end_position())
+        .unwrap()
+        .cost
+}
+
+pub fn part2(risk_levels: &Cave) -> u32 {
+    // Using the full map, what is the lowest total risk of any path from the
+    // top left to the bottom right?
+    let full_map = risk_levels.expand();
+    full_map
+        .lowest_risk_path(full_map.start_position(), full_map.end_position())
+        .unwrap()
+        .cost
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const EXAMPLE: &str = "\
+1163751742
+1381373672
+2136511328
+3694931569
+7463417111
+1319128137
+1359912421
+3125421639
+1293138521
+2311944581";
+
+    #[test]
+    fn part1_example() {
+        assert_eq!(part1(&parse(EXAMPLE).unwrap()), 40);
+    }
+
+    #[test]
+    fn lowest_risk_path() {
+        let cave = parse(EXAMPLE).unwrap();
+        let path = cave
+            .lowest_risk_path(cave.start_position(), cave.end_position())
+            .unwrap();
+        assert_eq!(path.start(), &cave.start_position());
+        assert_eq!(path.goal(), &cave.end_position());
+        // The risk of the starting position is not counted.
+        let risk: u32 = path.nodes[1..].iter().map(|&p| cave.get(p).unwrap()).sum();
+        assert_eq!(risk, path.cost);
+    }
+
+    #[test]
+    fn part2_example() {
+        assert_eq!(part2(&parse(EXAMPLE).unwrap()), 315);
+    }
+
+    #[test]
+    fn invalid_caves() {
+        let error = parse("19\n10").unwrap_err();
+        assert_eq!((error.line(), error.column(), error.text()), (2, 2, "0"));
+        assert_eq!(parse("").unwrap_err().message(), "Missing risk levels");
+    }
+}
@@ -0,0 +1,338 @@
+use crate::error::char_at;
+use crate::ParseError;
+use std::str::FromStr;
+
+pub type Version = u32;
+pub type Value = u64;
+
+const fn to_bits(b: u8) -> [u32; 4] {
+    match b {
+        b'0' => [0, 0, 0, 0],
+        b'1' => [0, 0, 0, 1],
+        b'2' => [0, 0, 1, 0],
+        b'3' => [0, 0, 1, 1],
+        b'4' => [0, 1, 0, 0],
+        b'5' => [0, 1, 0, 1],
+        b'6' => [0, 1, 1, 0],
+        b'7' => [0, 1, 1, 1],
+        b'8' => [1, 0, 0, 0],
+        b'9' => [1, 0, 0, 1],
+        b'A' => [1, 0, 1, 0],
+        b'B' => [1, 0, 1, 1],
+        b'C' => [1, 1, 0, 0],
+        b'D' => [1, 1, 0, 1],
+        b'E' => [1, 1, 1, 0],
+        b'F' => [1, 1, 1, 1],
+        _ => unreachable!(),
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum OpType {
+    Sum,
+    Product,
+    Minimum,
+    Maximum,
+    Value,
+    GreaterThan,
+    LessThan,
+    EqualTo,
+}
+
+impl From<u32> for OpType {
+    fn from(type_id: u32) -> Self {
+        match type_id {
+            0 => OpType::Sum,
+            1 => OpType::Product,
+            2 => OpType::Minimum,
+            3 => OpType::Maximum,
+            4 => OpType::Value,
+            5 => OpType::GreaterThan,
+            6 => OpType::LessThan,
+            7 => OpType::EqualTo,
+            _ => unimplemented!(),
+        }
+    }
+}
+
+pub trait BitIterator: Iterator<Item = u32> {
+    fn group(&mut self, n: usize) -> u32 {
+        self.take(n).fold(0, |acc, bit| (acc << 1) + bit)
+    }
+}
+
+impl<T: Iterator<Item = u32>> BitIterator for T {}
+
+#[derive(Debug, Clone)]
+pub enum Packet {
+    Literal(LiteralPacket),
+    Operator(OperatorPacket),
+}
+
+impl Packet {
+    pub fn new<I: BitIterator>(iter: &mut I) -> Self {
+        let version = iter.group(3);
+        let type_id = OpType::from(iter.group(3));
+        match type_id {
+            OpType::Value => Packet::Literal(LiteralPacket::new(version, type_id, iter)),
+            _ => Packet::Operator(OperatorPacket::new(version, type_id, iter)),
+        }
+    }
+
+    pub fn versions_iter(&self) -> Box<dyn Iterator<Item = Version> + '_> {
+        match self {
+            Packet::Literal(p) => Box::new(p.versions_iter()),
+            Packet::Operator(p) => Box::new(p.versions_iter()),
+        }
+    }
+
+    pub fn value(&self) -> Value {
+        match self {
+            Packet::Literal(p) => p.value(),
+            Packet::Operator(p) => p.value(),
+        }
+    }
+}
+
+impl FromStr for Packet {
+    type Err = ParseError;
+
+    fn from_str(input: &str) -> Result<Self, Self::Err> {
+        // `to_bits` only handles uppercase hexadecimal digits.
+        if let Some(idx) = input.find(|c: char| !matches!(c, '0'..='9' | 'A'..='F')) {
+            return Err(ParseError::new(
+                "Invalid hexadecimal digit",
+                input,
+                char_at(input, idx),
+            ));
+        }
+
+        if input.is_empty() {
+            return Err(ParseError::missing("Missing packet", input));
+        }
+
+        let mut bit_iterator = input.bytes().flat_map(to_bits);
+        let packet = Packet::new(&mut bit_iterator);
+        if let Packet::Operator(operator) = &packet {
+            operator
+                .check()
+                .map_err(|message| ParseError::new(message, input, input))?;
+        }
+
+        Ok(packet)
+    }
+}
+
+#[allow(dead_code)]
+#[derive(Debug, Clone)]
+pub struct LiteralPacket {
+    version: Version,
+    type_id: OpType,
+    value: u64,
+}
+
+impl LiteralPacket {
+    pub fn new<I: BitIterator>(version: u32, type_id: OpType, iter: &mut I) -> Self {
+        let mut value: Value = 0;
+        let mut not_last = 1;
+
+        while not_last == 1 {
+            not_last = iter.group(1);
+            value <<= 4;
+            value += Value::from(iter.group(4));
+        }
+
+        LiteralPacket {
+            version,
+            type_id,
+            value,
+        }
+    }
+
+    pub fn versions_iter(&self) -> impl Iterator<Item = Version> {
+        std::iter::once(self.version)
+    }
+
+    pub fn value(&self) -> Value {
+        self.value
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct OperatorPacket {
+    version: Version,
+    type_id: OpType,
+    packets: Vec<Packet>,
+}
+
+impl OperatorPacket {
+    pub fn new<I: BitIterator>(version: u32, type_id: OpType, iter: &mut I) -> Self {
+        let packets = if iter.group(1) == 0 {
+            Self::by_length(iter)
+        } else {
+            Self::by_nbr(iter)
+        };
+
+        OperatorPacket {
+            version,
+            type_id,
+            packets,
+        }
+    }
+
+    pub fn versions_iter(&self) -> impl Iterator<Item = Version> + '_ {
+        std::iter::once(self.version).chain(self.packets.iter().flat_map(Packet::versions_iter))
+    }
+
+    pub fn value(&self) -> Value {
+        let mut values = self.packets.iter().map(Packet::value);
+        match self.type_id {
+            OpType::Sum => values.sum(),
+            OpType::Product => values.product(),
+            OpType::Minimum => values.min().unwrap(),
+            OpType::Maximum => values.max().unwrap(),
+            OpType::Value => unreachable!(),
+            OpType::GreaterThan => {
+                if values.next().unwrap() > values.next().unwrap() {
+                    1
+                } else {
+                    0
+                }
+            }
+            OpType::LessThan => {
+                if values.next().unwrap() < values.next().unwrap() {
+                    1
+                } else {
+                    0
+                }
+            }
+            OpType::EqualTo => {
+                if values.next().unwrap() == values.next().unwrap() {
+                    1
+                } else {
+                    0
+                }
+            }
+        }
+    }
+
+    // Whether this operator and those below it have the sub-packets they need
+    // to be evaluated.
+    fn check(&self) -> Result<(), String> {
+        let count = self.packets.len();
+        match self.type_id {
+            OpType::Minimum | OpType::Maximum if count == 0 => {
+                return Err(format!("{:?} packet without sub-packets", self.type_id));
+            }
+            OpType::GreaterThan | OpType::LessThan | OpType::EqualTo if count != 2 => {
+                return Err(format!(
+                    "{:?} packet with {} sub-packets instead of 2",
+                    self.type_id, count
+                ));
+            }
+            _ => {}
+        }
+
+        self.packets.iter().try_for_each(|packet| match packet {
+            Packet::Operator(operator) => operator.check(),
+            Packet::Literal(_) => Ok(()),
+        })
+    }
+
+    #[allow(clippy::needless_collect)]
+    fn by_length<I: BitIterator>(iter: &mut I) -> Vec<Packet> {
+        let l = iter.group(15);
+
+        // Have to collect, sadly, given the recursive nature of the
+        // construction. Otherwise, we get a E0275 compiler error. So it is not
+        // "needless".
+        let bits: Vec<u32> = iter.take(l as usize).collect();
+        let mut iter = bits.into_iter().peekable();
+
+        let mut packets = Vec::new();
+        while iter.peek().is_some() {
+            packets.push(Packet::new(&mut iter));
+        }
+
+        packets
+    }
+
+    fn by_nbr<I: BitIterator>(iter: &mut I) -> Vec<Packet> {
+        let l = iter.group(11);
+        let mut packets = Vec::new();
+
+        for _ in 0..l {
+            packets.push(Packet::new(iter));
+        }
+
+        packets
+    }
+}
+
+pub fn parse(input: &str) -> Result<Packet, ParseError> {
+    input.trim().parse().map_err(|e: ParseError| e.on_day(16))
+}
+
+pub fn part1(outermost: &Packet) -> Version {
+    // Decode the structure of your hexadecimal-encoded BITS transmission; what
+    // do you get if you add up the version numbers in all packets?
+    outermost.versions_iter().sum()
+}
+
+pub fn part2(outermost: &Packet) -> Value {
+    // What do you get if you evaluate the expression represented by your
+    // hexadecimal-encoded BITS transmission?
+    outermost.value()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn value(transmission: &str) -> Value {
+        parse(transmission).unwrap().value()
+    }
+
+    #[test]
+    fn part1_examples() {
+        assert_eq!(part1(&parse("8A004A801A8002F478").unwrap()), 16);
+        assert_eq!(part1(&parse("620080001611562C8802118E34").unwrap()), 12);
+        assert_eq!(part1(&parse("C0015000016115A2E0802F182340").unwrap()), 23);
+        assert_eq!(part1(&parse("A0016C880162017C3686B18A3D4780").unwrap()), 31);
+    }
+
+    #[test]
+    fn part2_example() {
+        assert_eq!(part2(&parse("9C0141080250320F1802104A08").unwrap()), 1);
+    }
+
+    #[test]
+    fn literal_value() {
+        assert_eq!(value("D2FE28"), 2021);
+    }
+
+    #[test]
+    fn operator_values() {
+        assert_eq!(value("C200B40A82"), 3);
+        assert_eq!(value("04005AC33890"), 54);
+        assert_eq!(value("880086C3E88112"), 7);
+        assert_eq!(value("CE00C43D881120"), 9);
+        assert_eq!(value("D8005AC2A8F0"), 1);
+        assert_eq!(value("F600BC2D8F"), 0);
+        assert_eq!(value("9C005AC2F8F0"), 0);
+    }
+
+    #[test]
+    fn missing_sub_packets() {
+        // A minimum packet without sub-packets.
+        let error = parse("08").unwrap_err();
+        assert_eq!(error.message(), "Minimum packet without sub-packets");
+        // A greater than packet with a single literal, inside a sum.
+        let error = parse("020045801102").unwrap_err();
+        assert_eq!(
+            error.message(),
+            "GreaterThan packet with 1 sub-packets instead of 2"
+        );
+        assert_eq!(parse("\n").unwrap_err().message(), "Missing packet");
+    }
+}
@@ -0,0 +1,177 @@
+use crate::error::parse_number;
+use crate::ParseError;
+use std::cmp::{Ordering, PartialOrd};
+use std::ops::RangeInclusive;
+use std::str::FromStr;
+
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub struct Position {
+    x: i32,
+    y: i32,
+}
+
+impl Position {
+    pub fn new(x: i32, y: i32) -> Self {
+        Position { x, y }
+    }
+}
+
+impl std::ops::Add<Velocity> for Position {
+    type Output = Self;
+
+    fn add(self, rhs: Velocity) -> Self::Output {
+        Position::new(self.x + rhs.x, self.y + rhs.y)
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
+pub struct Velocity {
+    x: i32,
+    y: i32,
+}
+
+impl Velocity {
+    pub fn new(x: i32, y: i32) -> Self {
+        Velocity { x, y }
+    }
+
+    pub fn drag(self) -> Self {
+        Velocity::new((self.x - 1).max(0), self.y - 1)
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
+pub struct Probe {
+    position: Position,
+    velocity: Velocity,
+}
+
+impl Probe {
+    pub fn new(dx: i32, dy: i32) -> Self {
+        Probe {
+            position: Position::default(),
+            velocity: Velocity::new(dx, dy),
+        }
+    }
+}
+
+impl Iterator for Probe {
+    type Item = Position;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.position = self.position + self.velocity;
+        self.velocity = self.velocity.drag();
+        Some(self.position)
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct TargetArea {
+    x_range: RangeInclusive<i32>,
+    y_range: RangeInclusive<i32>,
+}
+
+// Use this to determine whether a position is within the target area.
+impl PartialEq<TargetArea> for Position {
+    fn eq(&self, area: &TargetArea) -> bool {
+        area.x_range.contains(&self.x) && area.y_range.contains(&self.y)
+    }
+}
+
+// Use this to determine whether a position is within the target area (Equal) or
+// past it (Greater), defined as:
+//      y < min(y_range) OR x > max(x_range)
+// This makes sense as x is non-decreasing, while y is eventually decreasing
+// with time.
+impl PartialOrd<TargetArea> for Position {
+    fn partial_cmp(&self, area: &TargetArea) -> Option<Ordering> {
+        Some(
+            self.y
+                .cmp(area.y_range.start())
+                .reverse()
+                .then(self.x.cmp(area.x_range.end())),
+        )
+    }
+}
+
+fn parse_range(line: &str, text: &str, axis: &str) -> Result<RangeInclusive<i32>, ParseError> {
+    let (min, max) = text
+        .strip_prefix(axis)
+        .and_then(|range| range.split_once(".."))
+        .ok_or_else(|| ParseError::new(format!("Invalid {}range", axis), line, text))?;
+
+    Ok(RangeInclusive::new(
+        parse_number(line, min)?,
+        parse_number(line, max)?,
+    ))
+}
+
+impl FromStr for TargetArea {
+    type Err = ParseError;
+
+    fn from_str(line: &str) -> Result<Self, Self::Err> {
+        let (x, y) = line
+            .strip_prefix("target area: ")
+            .and_then(|area| area.split_once(", "))
+            .ok_or_else(|| ParseError::new("Invalid target area", line, line))?;
+
+        Ok(TargetArea {
+            x_range: parse_range(line, x, "x=")?,
+            y_range: parse_range(line, y, "y=")?,
+        })
+    }
+}
+
+pub fn parse(input: &str) -> Result<TargetArea, ParseError> {
+    input.trim().parse().map_err(|e: ParseError| e.on_day(17))
+}
+
+pub fn part1(target: &TargetArea) -> i32 {
+    // Find the initial velocity that causes the probe to reach the highest y
+    // position and still eventually be within the target area after any step.
+    // What is the highest y position it reaches on this trajectory?
+    // We can treat the x and y positions separately, which simplifies greatly
+    // the analysis. A positive y velocity will draw a parabola which will end
+    // up back at 0 (0 -> 1 -> 1 -> 0) with -(y + 1) velocity: thus, at the next
+    // step, it will reach -(y + 1). By setting this as the lowest point of the
+    // range over y, we can determine the initial y (positive).
+    let initial_y = -(target.y_range.start() + 1);
+    (initial_y * (initial_y + 1)) / 2
+}
+
+pub fn part2(target: &TargetArea) -> usize {
+    // How many distinct initial velocity values cause the probe to be within
+    // the target area after any step?
+    // Let's simulate all reasonable probe trajectories.
+    let min_dx = (2.0 * *target.x_range.start() as f64).sqrt() as i32;
+    let max_dx = *target.x_range.end();
+    let min_dy = *target.y_range.start();
+
+    (min_dx..=max_dx)
+        .flat_map(|dx| (min_dy..=min_dy.abs()).map(move |dy| Probe::new(dx, dy)))
+        .filter(|probe| {
+            probe
+                .into_iter()
+                .take_while(|p| p <= target)
+                .any(|p| p == *target)
+        })
+        .count()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const EXAMPLE: &str = "\
+target area: x=20..30, y=-10..-5";
+
+    #[test]
+    fn part1_example() {
+        assert_eq!(part1(&parse(EXAMPLE).unwrap()), 45);
+    }
+
+    #[test]
+    fn part2_example() {
+        assert_eq!(part2(&parse(EXAMPLE).unwrap()), 112);
+    }
+}
@@ -0,0 +1,332 @@
+use crate::error::{char_at, parse_lines};
+use crate::ParseError;
+use std::str::FromStr;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Token {
+    Open,
+    Close,
+    Comma,
+    Regular(u32),
+}
+
+impl From<&Token> for char {
+    fn from(token: &Token) -> char {
+        match token {
+            Token::Open => '[',
+            Token::Close => ']',
+            Token::Comma => ',',
+            Token::Regular(n) => char::from_digit(*n, 10).unwrap(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SnailfishNumber(Vec<Token>);
+
+impl SnailfishNumber {
+    pub fn reduce(&mut self) {
+        while self.explode() || self.split() {}
+    }
+
+    pub fn explode(&mut self) -> bool {
+        let mut current_level = 0;
+        for idx in 0..self.0.len() {
+            match self.0[idx] {
+                Token::Open => current_level += 1,
+                Token::Close => current_level -= 1,
+                Token::Comma => (),
+                Token::Regular(left) => {
+                    if current_level > 4 && matches!(self.0[idx + 1], Token::Comma) {
+                        if let Token::Regular(right) = self.0[idx + 2] {
+                            if let Some(Token::Regular(l)) = self.0[0..idx]
+                                .iter_mut()
+                                .rev()
+                                .find(|t| matches!(t, Token::Regular(_)))
+                            {
+                                *l += left;
+                            }
+                            if let Some(Token::Regular(r)) = self.0[idx + 3..]
+                                .iter_mut()
+                                .find(|t| matches!(t, Token::Regular(_)))
+                            {
+                                *r += right;
+                            }
+                            self.0.splice(idx - 1..idx + 4, [Token::Regular(0)]);
+                            return true;
+                        }
+                    }
+                }
+            }
+        }
+
+        false
+    }
+
+    pub fn split(&mut self) -> bool {
+        for idx in 0..self.0.len() {
+            match self.0[idx] {
+                Token::Open | Token::Close | Token::Comma => (),
+                Token::Regular(n) => {
+                    if n > 9 {
+                        let left = n / 2;
+                        let right = n - left;
+                        self.0.splice(
+                            idx..idx + 1,
+                            [
+                                Token::Open,
+                                Token::Regular(left),
+                                Token::Comma,
+                                Token::Regular(right),
+                                Token::Close,
+                            ],
+                        );
+                        return true;
+                    }
+                }
+            }
+        }
+
+        false
+    }
+
+    pub fn magnitude(&self) -> u32 {
+        let mut total = 0;
+        let mut multiplier = 1;
+
+        for token in &self.0 {
+            match token {
+                Token::Open => multiplier *= 3,
+                Token::Close => multiplier /= 2,
+                Token::Comma => {
+                    multiplier /= 3;
+                    multiplier *= 2;
+                }
+                Token::Regular(v) => total += v * multiplier,
+            }
+        }
+
+        total
+    }
+}
+
+impl std::ops::Add for SnailfishNumber {
+    type Output = Self;
+
+    fn add(self, other: Self) -> Self::Output {
+        let mut s = SnailfishNumber(
+            std::iter::once(Token::Open)
+                .chain(self.0)
+                .chain(std::iter::once(Token::Comma))
+                .chain(other.0)
+                .chain(std::iter::once(Token::Close))
+                .collect(),
+        );
+
+        s.reduce();
+        s
+    }
+}
+
+impl FromStr for SnailfishNumber {
+    type Err = ParseError;
+
+    fn from_str(input: &str) -> Result<Self, Self::Err> {
+        let tokens = input
+            .char_indices()
+            .map(|(idx, c)| match c {
+                '[' => Ok(Token::Open),
+                ']' => Ok(Token::Close),
+                ',' => Ok(Token::Comma),
+                c if c.is_ascii_digit() => Ok(Token::Regular(u32::from(c as u8 - b'0'))),
+                _ => Err(ParseError::new(
+                    "Invalid character",
+                    input,
+                    char_at(input, idx),
+                )),
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+
+        // The reduction relies on the tokens forming a single pair, such as
+        // `[[1,2],3]`.
+        let mut expected = vec![Token::Open];
+        let mut pair_start = true;
+        for (idx, token) in tokens.iter().enumerate() {
+            let valid = match (expected.pop(), token) {
+                (Some(Token::Open), Token::Open) => {
+                    expected.extend([Token::Close, Token::Open, Token::Comma, Token::Open]);
+                    true
+                }
+                (Some(Token::Open), Token::Regular(_)) => !pair_start,
+                (Some(e), t) => e == *t,
+                (None, _) => false,
+            };
+            pair_start = false;
+            if !valid {
+                let text = char_at(input, idx);
+                return Err(ParseError::new("Unexpected character", input, text));
+            }
+        }
+        if !expected.is_empty() {
+            return Err(ParseError::missing("Incomplete snailfish number", input));
+        }
+
+        Ok(SnailfishNumber(tokens))
+    }
+}
+
+impl std::fmt::Display for SnailfishNumber {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(f, "{}", self.0.iter().map(char::from).collect::<String>())
+    }
+}
+
+pub fn parse(input: &str) -> Result<Vec<SnailfishNumber>, ParseError> {
+    let numbers = parse_lines(input, str::parse).map_err(|e| e.on_day(18))?;
+    if numbers.is_empty() {
+        return Err(ParseError::missing("Missing snailfish numbers", input).on_day(18));
+    }
+
+    Ok(numbers)
+}
+
+pub fn part1(numbers: &[SnailfishNumber]) -> u32 {
+    // Add up all of the snailfish numbers from the homework assignment in the
+    // order they appear. What is the magnitude of the final sum?
+    let mut sum = numbers[0].clone();
+    for number in &numbers[1..] {
+        sum = sum + number.clone();
+    }
+
+    sum.magnitude()
+}
+
+pub fn part2(numbers: &[SnailfishNumber]) -> u32 {
+    // What is the largest magnitude of any sum of two different snailfish
+    // numbers from the homework assignment?
+    numbers
+        .iter()
+        .flat_map(|first| {
+            // Needs a `filter` to avoid reusing the same number.
+            numbers
+                .iter()
+                .map(|second| (first.clone() + second.clone()).magnitude())
+        })
+        .max()
+        .unwrap()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const EXAMPLE: &str = "\
+[[[0,[5,8]],[[1,7],[9,6]]],[[4,[1,2]],[[1,4],2]]]
+[[[5,[2,8]],4],[5,[[9,9],0]]]
+[6,[[[6,2],[5,6]],[[7,6],[4,7]]]]
+[[[6,[0,7]],[0,9]],[4,[9,[9,0]]]]
+[[[7,[6,4]],[3,[1,3]]],[[[5,5],1],9]]
+[[6,[[7,3],[3,2]]],[[[3,8],[5,7]],4]]
+[[[[5,4],[7,7]],8],[[8,3],8]]
+[[9,3],[[9,9],[6,[4,9]]]]
+[[2,[[7,7],7]],[[5,8],[[9,3],[0,2]]]]
+[[[[5,2],5],[8,[3,7]]],[[5,[7,5]],[4,4]]]";
+
+    #[test]
+    fn part1_example() {
+        assert_eq!(part1(&parse(EXAMPLE).unwrap()), 4140);
+    }
+
+    #[test]
+    fn part2_example() {
+        assert_eq!(part2(&parse(EXAMPLE).unwrap()), 3993);
+    }
+
+    fn number(s: &str) -> SnailfishNumber {
+        s.parse().unwrap()
+    }
+
+    fn exploded(s: &str) -> String {
+        let mut n = number(s);
+        assert!(n.explode());
+        n.to_string()
+    }
+
+    #[test]
+    fn explode() {
+        assert_eq!(exploded("[[[[[9,8],1],2],3],4]"), "[[[[0,9],2],3],4]");
+        assert_eq!(exploded("[7,[6,[5,[4,[3,2]]]]]"), "[7,[6,[5,[7,0]]]]");
+        assert_eq!(exploded("[[6,[5,[4,[3,2]]]],1]"), "[[6,[5,[7,0]]],3]");
+        assert_eq!(
+            exploded("[[3,[2,[1,[7,3]]]],[6,[5,[4,[3,2]]]]]"),
+            "[[3,[2,[8,0]]],[9,[5,[4,[3,2]]]]]"
+        );
+        assert_eq!(
+            exploded("[[3,[2,[8,0]]],[9,[5,[4,[3,2]]]]]"),
+            "[[3,[2,[8,0]]],[9,[5,[7,0]]]]"
+        );
+        assert!(!number("[[[[0,9],2],3],4]").explode());
+    }
+
+    #[test]
+    fn reduction_steps() {
+        // The sum, before any reduction.
+        let (a, b) = (number("[[[[4,3],4],4],[7,[[8,4],9]]]"), number("[1,1]"));
+        let mut n = SnailfishNumber(
+            [
+                vec![Token::Open],
+                a.0,
+                vec![Token::Comma],
+                b.0,
+                vec![Token::Close],
+            ]
+            .concat(),
+        );
+
+        assert!(n.explode());
+        assert_eq!(n.to_string(), "[[[[0,7],4],[7,[[8,4],9]]],[1,1]]");
+        // The second explosion leaves 15 and 13, which must be split.
+        assert!(n.explode());
+        assert!(!n.explode());
+        assert!(n.split());
+        assert!(n.split());
+        assert!(n.explode());
+        assert_eq!(n.to_string(), "[[[[0,7],4],[[7,8],[6,0]]],[8,1]]");
+        assert!(!n.explode());
+        assert!(!n.split());
+    }
+
+    #[test]
+    fn addition() {
+        let sum = number("[[[[4,3],4],4],[7,[[8,4],9]]]") + number("[1,1]");
+        assert_eq!(sum.to_string(), "[[[[0,7],4],[[7,8],[6,0]]],[8,1]]");
+    }
+
+    #[test]
+    fn magnitude() {
+        assert_eq!(number("[[1,2],[[3,4],5]]").magnitude(), 143);
+        assert_eq!(
+            number("[[[[0,7],4],[[7,8],[6,0]]],[8,1]]").magnitude(),
+            1384
+        );
+        assert_eq!(number("[[[[1,1],[2,2]],[3,3]],[4,4]]").magnitude(), 445);
+        assert_eq!(number("[[[[3,0],[5,3]],[4,4]],[5,5]]").magnitude(), 791);
+        assert_eq!(number("[[[[5,0],[7,4]],[5,5]],[6,6]]").magnitude(), 1137);
+        assert_eq!(
+            number("[[[[8,7],[7,7]],[[8,6],[7,7]]],[[[0,7],[6,6]],[8,7]]]").magnitude(),
+            3488
+        );
+    }
+
+    #[test]
+    fn malformed_numbers() {
+        assert_eq!(parse("[1,2]\n[1,[2,]]").unwrap_err().column(), 7);
+        assert_eq!(parse("[1,2").unwrap_err().column(), 5);
+        assert!(parse("1").is_err());
+        assert!(parse("[1,2]]").is_err());
+        assert_eq!(
+            parse("").unwrap_err().message(),
+            "Missing snailfish numbers"
+        );
+    }
+}
@@ -0,0 +1,455 @@
+use crate::error::parse_number;
+use crate::ParseError;
+use counter::Counter;
+use std::collections::{HashSet, VecDeque};
+use std::iter::FromIterator;
+use std::str::FromStr;
+use Alignment::*;
+
+const ALIGNMENTS: [Alignment; 24] = [
+    XYZ, XzY, Xyz, XZy, yXZ, ZXY, YXz, zXy, xyZ, xzy, xYz, xZY, YxZ, Zxy, yxz, zxY, zYX, YZX, ZyX,
+    yzX, zyx, yZx, ZYx, Yzx,
+];
+
+#[allow(non_camel_case_types, clippy::upper_case_acronyms)]
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Alignment {
+    #[default]
+    XYZ,
+    XzY,
+    Xyz,
+    XZy,
+    yXZ,
+    ZXY,
+    YXz,
+    zXy,
+    xyZ,
+    xzy,
+    xYz,
+    xZY,
+    YxZ,
+    Zxy,
+    yxz,
+    zxY,
+    zYX,
+    YZX,
+    ZyX,
+    yzX,
+    zyx,
+    yZx,
+    ZYx,
+    Yzx,
+}
+
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Position {
+    x: i32,
+    y: i32,
+    z: i32,
+}
+
+impl Position {
+    pub fn new(x: i32, y: i32, z: i32) -> Self {
+        Position { x, y, z }
+    }
+
+    pub fn align(self, alignment: Alignment) -> Position {
+        let Self { x, y, z } = self;
+        match alignment {
+            XYZ => Position::new(x, y, z),
+            XzY => Position::new(x, -z, y),
+            Xyz => Position::new(x, -y, -z),
+            XZy => Position::new(x, z, -y),
+            yXZ => Position::new(-y, x, z),
+            ZXY => Position::new(z, x, y),
+            YXz => Position::new(y, x, -z),
+            zXy => Position::new(-z, x, -y),
+            xyZ => Position::new(-x, -y, z),
+            xzy => Position::new(-x, -z, -y),
+            xYz => Position::new(-x, y, -z),
+            xZY => Position::new(-x, z, y),
+            YxZ => Position::new(y, -x, z),
+            Zxy => Position::new(z, -x, -y),
+            yxz => Position::new(-y, -x, -z),
+            zxY => Position::new(-z, -x, y),
+            zYX => Position::new(-z, y, x),
+            YZX => Position::new(y, z, x),
+            ZyX => Position::new(z, -y, x),
+            yzX => Position::new(-y, -z, x),
+            zyx => Position::new(-z, -y, -x),
+            yZx => Position::new(-y, z, -x),
+            ZYx => Position::new(z, y, -x),
+            Yzx => Position::new(y, -z, -x),
+        }
+    }
+}
+
+impl std::ops::Sub for Position {
+    type Output = Distance;
+
+    fn sub(self, other: Self) -> Self::Output {
+        Distance::new(self.x - other.x, self.y - other.y, self.z - other.z)
+    }
+}
+
+impl FromStr for Position {
+    type Err = ParseError;
+
+    fn from_str(position: &str) -> Result<Self, Self::Err> {
+        let line = position.trim();
+        let mut iter = line.split(',');
+        if let (Some(x), Some(y), Some(z), None) =
+            (iter.next(), iter.next(), iter.next(), iter.next())
+        {
+            Ok(Position {
+                x: parse_number(line, x)?,
+                y: parse_number(line, y)?,
+                z: parse_number(line, z)?,
+            })
+        } else {
+            Err(ParseError::new("Expected 3 coordinates", line, line))
+        }
+    }
+}
+
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Distance {
+    dx: i32,
+    dy: i32,
+    dz: i32,
+}
+
+impl Distance {
+    pub fn new(dx: i32, dy: i32, dz: i32) -> Self {
+        Distance { dx, dy, dz }
+    }
+
+    pub fn manhattan_distance(self) -> i32 {
+        self.dx.abs() + self.dy.abs() + self.dz.abs()
+    }
+
+    pub fn all_alignments(self) -> impl Iterator<Item = (Alignment, Distance)> {
+        ALIGNMENTS
+            .into_iter()
+            .map(move |alignment| (alignment, self.align(alignment)))
+    }
+
+    pub fn align(self, alignment: Alignment) -> Distance {
+        let Self {
+            dx: x,
+            dy: y,
+            dz: z,
+        } = self;
+        match alignment {
+            XYZ => Distance::new(x, y, z),
+            XzY => Distance::new(x, -z, y),
+            Xyz => Distance::new(x, -y, -z),
+            XZy => Distance::new(x, z, -y),
+            yXZ => Distance::new(-y, x, z),
+            ZXY => Distance::new(z, x, y),
+            YXz => Distance::new(y, x, -z),
+            zXy => Distance::new(-z, x, -y),
+            xyZ => Distance::new(-x, -y, z),
+            xzy => Distance::new(-x, -z, -y),
+            xYz => Distance::new(-x, y, -z),
+            xZY => Distance::new(-x, z, y),
+            YxZ => Distance::new(y, -x, z),
+            Zxy => Distance::new(z, -x, -y),
+            yxz => Distance::new(-y, -x, -z),
+            zxY => Distance::new(-z, -x, y),
+            zYX => Distance::new(-z, y, x),
+            YZX => Distance::new(y, z, x),
+            ZyX => Distance::new(z, -y, x),
+            yzX => Distance::new(-y, -z, x),
+            zyx => Distance::new(-z, -y, -x),
+            yZx => Distance::new(-y, z, -x),
+            ZYx => Distance::new(z, y, -x),
+            Yzx => Distance::new(y, -z, -x),
+        }
+    }
+}
+
+#[derive(Debug, Default, Clone)]
+pub struct Scanner {
+    position: Position,
+    beacons: Vec<Position>,
+}
+
+impl Scanner {
+    pub fn add_beacon(&mut self, beacon: Position) {
+        self.beacons.push(beacon);
+    }
+
+    pub fn pairs(&self) -> impl Iterator<Item = (&Position, &Position)> + '_ {
+        self.beacons.iter().flat_map(|first| {
+            self.beacons
+                .iter()
+                .filter(move |second| first != *second)
+                .map(move |second| (first, second))
+        })
+    }
+
+    pub fn distances(&self) -> impl Iterator<Item = Distance> + '_ {
+        self.pairs().map(|(first, second)| *first - *second)
+    }
+}
+
+#[derive(Debug, Default, Clone)]
+pub struct BeaconSystem {
+    scanners: HashSet<Position>,
+    beacons: HashSet<Position>,
+}
+
+impl FromIterator<Scanner> for BeaconSystem {
+    fn from_iter<I: IntoIterator<Item = Scanner>>(iter: I) -> Self {
+        let mut scanners = HashSet::new();
+        let mut beacons = HashSet::new();
+
+        let mut to_do = VecDeque::new();
+        for scanner in iter {
+            to_do.push_back(scanner);
+        }
+
+        // The first one will be the canonical scanner (center of the grid and
+        // alignment).
+        if let Some(scanner) = to_do.pop_front() {
+            scanners.insert(scanner.position);
+            beacons.extend(scanner.beacons);
+        }
+        let mut pairs: Vec<(Position, Position)> = beacons
+            .iter()
+            .flat_map(|first| {
+                beacons
+                    .iter()
+                    .filter(move |second| first != *second)
+                    .map(move |second| (*first, *second))
+            })
+            .collect();
+        let mut distances: HashSet<Distance> = pairs
+            .iter()
+            .map(|(first, second)| *first - *second)
+            .collect();
+
+        while let Some(scanner) = to_do.pop_front() {
+            let counter: Counter<Alignment> = scanner
+                .distances()
+                .flat_map(|new_distance| {
+                    new_distance.all_alignments().filter_map(|(a, d)| {
+                        if distances.contains(&d) {
+                            Some(a)
+                        } else {
+                            None
+                        }
+                    })
+                })
+                .collect();
+
+            if let Some(&(alignment, count)) = counter.most_common().first() {
+                if count >= 66 {
+                    let new_beacons: Vec<Position> = scanner
+                        .beacons
+                        .into_iter()
+                        .map(|b| b.align(alignment))
+                        .collect();
+                    let ((q0, _), (p0, _)) = new_beacons
+                        .iter()
+                        .flat_map(|first| {
+                            new_beacons
+                                .iter()
+                                .filter(move |second| first != *second)
+                                .map(move |second| (first, second))
+                        })
+                        .find_map(|p| {
+                            let new_d = *p.0 - *p.1;
+                            pairs.iter().find(|&&q| q.0 - q.1 == new_d).map(|&q| (q, p))
+                        })
+                        .unwrap();
+                    let scanner_position = Position::new(q0.x - p0.x, q0.y - p0.y, q0.z - p0.z);
+                    scanners.insert(scanner_position);
+                    beacons.extend(new_beacons.into_iter().map(|beacon| {
+                        Position::new(
+                            scanner_position.x + beacon.x,
+                            scanner_position.y + beacon.y,
+                            scanner_position.z + beacon.z,
+                        )
+                    }));
+                    pairs = beacons
+                        .iter()
+                        .flat_map(|first| {
+                            beacons
+                                .iter()
+                                .filter(move |second| first != *second)
+                                .map(move |second| (*first, *second))
+                        })
+                        .collect();
+                    distances = pairs
+                        .iter()
+                        .map(|(first, second)| *first - *second)
+                        .collect();
+                } else {
+                    to_do.push_back(scanner);
+                }
+            } else {
+                to_do.push_back(scanner);
+            }
+        }
+
+        BeaconSystem { scanners, beacons }
+    }
+}
+
+pub fn parse(input: &str) -> Result<Vec<Scanner>, ParseError> {
+    let mut scanners = Vec::new();
+
+    for (idx, line) in input.lines().enumerate() {
+        if line.starts_with("---") {
+            scanners.push(Scanner::default());
+        } else if !line.is_empty() {
+            let scanner = scanners.last_mut().ok_or_else(|| {
+                ParseError::new("Missing scanner header", line, line)
+                    .offset_lines(idx)
+                    .on_day(19)
+            })?;
+            let beacon = line
+                .parse()
+                .map_err(|e: ParseError| e.offset_lines(idx).on_day(19))?;
+            scanner.add_beacon(beacon);
+        }
+    }
+
+    if scanners.is_empty() {
+        return Err(ParseError::missing("Missing scanners", input).on_day(19));
+    }
+
+    Ok(scanners)
+}
+
+pub fn part1(scanners: &[Scanner]) -> usize {
+    // Assemble the full map of beacons. How many beacons are there?
+    let system: BeaconSystem = scanners.iter().cloned().collect();
+    system.beacons.len()
+}
+
+pub fn part2(scanners: &[Scanner]) -> i32 {
+    // What is the largest Manhattan distance between any two scanners?
+    let system: BeaconSystem = scanners.iter().cloned().collect();
+    system
+        .scanners
+        .iter()
+        .flat_map(|first| {
+            system
+                .scanners
+                .iter()
+                .map(|second| (*first - *second).manhattan_distance())
+        })
+        .max()
+        .unwrap()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const EXAMPLE: &str = "\
+--- scanner 0 ---
+113,892,-197
+139,-461,-824
+70,527,460
+283,495,783
+781,197,-708
+-340,537,557
+-78,225,-331
+111,-780,-454
+-735,-560,19
+-204,523,-183
+793,258,-647
+-515,304,136
+-100,884,116
+293,-774,281
+247,771,496
+-110,469,-190
+-556,351,-661
+299,-88,-799
+242,273,716
+858,-628,-307
+612,-393,-86
+485,709,-140
+514,-799,-661
+116,493,188
+269,408,-516
+558,-772,255
+433,-802,-752
+514,-493,327
+673,-312,-636
+726,-532,531
+228,-31,-779
+-454,857,-849
+892,775,-258
+
+--- scanner 1 ---
+493,-35,325
+863,-837,-341
+-546,166,50
+-498,428,121
+-869,330,-226
+994,333,712
+812,-402,706
+591,-448,425
+247,186,560
+806,678,20
+432,515,244
+213,137,-843
+836,395,-476
+591,540,731
+992,76,-960
+966,703,393
+547,-376,704
+379,-652,464
+672,631,734
+822,-904,-563
+312,526,-326
+877,658,-37
+620,19,-777
+324,587,-265
+858,-617,-839
+989,-309,-561
+
+--- scanner 2 ---
+-374,-14,955
+-559,-361,772
+-892,362,323
+-233,18,711
+-751,-873,983
+93,423,876
+-183,-577,471
+259,144,-348
+673,-334,907
+453,-339,409
+-704,464,829
+417,-162,653
+-527,-802,-306
+73,8,296
+145,-208,687
+-690,-885,922
+514,248,643
+-240,-205,288
+-301,-984,405
+-226,112,657
+194,602,42
+740,-375,685";
+
+    #[test]
+    fn part1_example() {
+        assert_eq!(part1(&parse(EXAMPLE).unwrap()), 39);
+    }
+
+    #[test]
+    fn part2_example() {
+        assert_eq!(part2(&parse(EXAMPLE).unwrap()), 2609);
+    }
+
+    #[test]
+    fn missing_scanners() {
+        assert_eq!(parse("\n").unwrap_err().message(), "Missing scanners");
+    }
+}
@@ -0,0 +1,691 @@
+use crate::error::parse_number;
+use crate::ParseError;
+use std::fmt;
+
+// Bounds the expansion of nested `repeat` blocks.
+const MAX_COMMANDS: usize = 10_000_000;
+
+/// The manoeuvres the submarine knows, which the commands of a script are
+/// expanded into.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Command {
+    Forward(i32),
+    Down(i32),
+    Up(i32),
+    Back(i32),
+    Hold,
+}
+
+impl fmt::Display for Command {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Command::Forward(d) => write!(f, "forward {}", d),
+            Command::Down(d) => write!(f, "down {}", d),
+            Command::Up(d) => write!(f, "up {}", d),
+            Command::Back(d) => write!(f, "back {}", d),
+            Command::Hold => write!(f, "hold"),
+        }
+    }
+}
+
+/// A movement model, i.e. a way of following the commands: `Position` for the
+/// first part of the puzzle, and `Submarine`, which aims, for the second.
+/// Each manoeuvre returns `None` if it overflows.
+pub trait Navigation: Copy + Default {
+    fn forward(self, d: i32) -> Option<Self>;
+    fn down(self, d: i32) -> Option<Self>;
+    fn up(self, d: i32) -> Option<Self>;
+    fn depth(&self) -> i32;
+    fn distance(&self) -> i32;
+
+    fn aim(&self) -> i32 {
+        0
+    }
+
+    fn back(self, d: i32) -> Option<Self> {
+        self.forward(d.checked_neg()?)
+    }
+
+    fn hold(self) -> Option<Self> {
+        Some(self)
+    }
+
+    fn checked_command(self, command: Command) -> Option<Self> {
+        match command {
+            Command::Forward(d) => self.forward(d),
+            Command::Down(d) => self.down(d),
+            Command::Up(d) => self.up(d),
+            Command::Back(d) => self.back(d),
+            Command::Hold => self.hold(),
+        }
+    }
+
+    fn command(self, command: Command) -> Self {
+        self.checked_command(command)
+            .unwrap_or_else(|| panic!("Overflow on {}", command))
+    }
+}
+
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct Position {
+    depth: i32,
+    distance: i32,
+}
+
+impl Navigation for Position {
+    fn forward(self, d: i32) -> Option<Self> {
+        Some(Position {
+            distance: self.distance.checked_add(d)?,
+            ..self
+        })
+    }
+
+    fn down(self, d: i32) -> Option<Self> {
+        Some(Position {
+            depth: self.depth.checked_add(d)?,
+            ..self
+        })
+    }
+
+    fn up(self, d: i32) -> Option<Self> {
+        Some(Position {
+            depth: self.depth.checked_sub(d)?,
+            ..self
+        })
+    }
+
+    fn depth(&self) -> i32 {
+        self.depth
+    }
+
+    fn distance(&self) -> i32 {
+        self.distance
+    }
+}
+
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct Submarine {
+    depth: i32,
+    distance: i32,
+    aim: i32,
+}
+
+impl Navigation for Submarine {
+    fn forward(self, d: i32) -> Option<Self> {
+        Some(Submarine {
+            depth: self.depth.checked_add(d.checked_mul(self.aim)?)?,
+            distance: self.distance.checked_add(d)?,
+            ..self
+        })
+    }
+
+    fn down(self, d: i32) -> Option<Self> {
+        Some(Submarine {
+            aim: self.aim.checked_add(d)?,
+            ..self
+        })
+    }
+
+    fn up(self, d: i32) -> Option<Self> {
+        Some(Submarine {
+            aim: self.aim.checked_sub(d)?,
+            ..self
+        })
+    }
+
+    fn depth(&self) -> i32 {
+        self.depth
+    }
+
+    fn distance(&self) -> i32 {
+        self.distance
+    }
+
+    fn aim(&self) -> i32 {
+        self.aim
+    }
+}
+
+/// A command of the scripts, such as `forward 5`.
+pub trait ScriptCommand {
+    fn name(&self) -> &str;
+
+    /// The manoeuvres for the given `argument`, a slice of `line` (which
+    /// errors should point into).
+    fn expand(&self, line: &str, argument: &str) -> Result<Vec<Command>, ParseError>;
+}
+
+/// A command taking an amount, mapped to a single manoeuvre.
+pub struct AmountCommand {
+    name: &'static str,
+    command: fn(i32) -> Command,
+}
+
+impl AmountCommand {
+    pub fn new(name: &'static str, command: fn(i32) -> Command) -> Self {
+        AmountCommand { name, command }
+    }
+}
+
+impl ScriptCommand for AmountCommand {
+    fn name(&self) -> &str {
+        self.name
+    }
+
+    fn expand(&self, line: &str, argument: &str) -> Result<Vec<Command>, ParseError> {
+        Ok(vec![(self.command)(parse_number(line, argument)?)])
+    }
+}
+
+/// `hold n`: stay in place for `n` steps.
+pub struct HoldCommand;
+
+impl ScriptCommand for HoldCommand {
+    fn name(&self) -> &str {
+        "hold"
+    }
+
+    fn expand(&self, line: &str, argument: &str) -> Result<Vec<Command>, ParseError> {
+        let steps: usize = parse_number(line, argument)?;
+        if steps > MAX_COMMANDS {
+            return Err(ParseError::new("Too many commands", line, argument));
+        }
+        Ok(vec![Command::Hold; steps])
+    }
+}
+
+/// Expands scripts into manoeuvres. Besides the registered commands, one per
+/// line, scripts can hold comments, from `#` to the end of the line, and
+/// blocks repeated a number of times:
+///
+/// ```text
+/// repeat 3 {
+///     down 1  # Dive.
+///     forward 2
+/// }
+/// ```
+///
+/// Blocks can also open and close on the same line as their commands, as in
+/// `repeat 3 { forward 1 }`.
+pub struct Interpreter {
+    commands: Vec<Box<dyn ScriptCommand>>,
+}
+
+impl Default for Interpreter {
+    /// An interpreter knowing `forward`, `down`, `up`, `back` and `hold`.
+    fn default() -> Self {
+        Interpreter::empty()
+            .with_command(AmountCommand::new("forward", Command::Forward))
+            .with_command(AmountCommand::new("down", Command::Down))
+            .with_command(AmountCommand::new("up", Command::Up))
+            .with_command(AmountCommand::new("back", Command::Back))
+            .with_command(HoldCommand)
+    }
+}
+
+impl Interpreter {
+    pub fn empty() -> Self {
+        Interpreter {
+            commands: Vec::new(),
+        }
+    }
+
+    /// Register a command, replacing any command with the same name.
+    pub fn with_command(mut self, command: impl ScriptCommand + 'static) -> Self {
+        self.commands.retain(|c| c.name() != command.name());
+        self.commands.push(Box::new(command));
+        self
+    }
+
+    pub fn parse(&self, script: &str) -> Result<Vec<Command>, ParseError> {
+        // The commands of the blocks being read, with their repetitions and
+        // the line opening them.
+        let mut blocks: Vec<(usize, Vec<Command>, usize)> = vec![(1, Vec::new(), 0)];
+
+        for (idx, line) in script.lines().enumerate() {
+            let code = line[..line.find('#').unwrap_or(line.len())].trim();
+            for statement in statements(code) {
+                let (name, argument) = statement
+                    .split_once(char::is_whitespace)
+                    .map_or((statement, ""), |(name, argument)| (name, argument.trim()));
+
+                match name {
+                    "}" if blocks.len() > 1 => {
+                        let (repetitions, commands, _) = blocks.pop().unwrap_or_default();
+                        let block = &mut blocks.last_mut().unwrap().1;
+                        let total = commands
+                            .len()
+                            .checked_mul(repetitions)
+                            .and_then(|n| n.checked_add(block.len()));
+                        if total.is_none_or(|n| n > MAX_COMMANDS) {
+                            let error = ParseError::new("Too many commands", line, statement);
+                            return Err(error.offset_lines(idx));
+                        }
+                        for _ in 0..repetitions {
+                            block.extend_from_slice(&commands);
+                        }
+                    }
+                    "}" => {
+                        let error = ParseError::new("Unexpected end of block", line, statement);
+                        return Err(error.offset_lines(idx));
+                    }
+                    "repeat" => {
+                        let repetitions = argument
+                            .strip_suffix('{')
+                            .ok_or_else(|| ParseError::missing("Missing {", line))
+                            .and_then(|n| parse_number(line, n.trim()))
+                            .map_err(|e| e.offset_lines(idx))?;
+                        blocks.push((repetitions, Vec::new(), idx));
+                    }
+                    _ => {
+                        let command = self
+                            .commands
+                            .iter()
+                            .find(|c| c.name() == name)
+                            .ok_or_else(|| ParseError::new("Invalid command", line, name))
+                            .and_then(|c| c.expand(line, argument))
+                            .map_err(|e| e.offset_lines(idx))?;
+                        let block = &mut blocks.last_mut().unwrap().1;
+                        if block.len() + command.len() > MAX_COMMANDS {
+                            let error = ParseError::new("Too many commands", line, statement);
+                            return Err(error.offset_lines(idx));
+                        }
+                        block.extend(command);
+                    }
+                }
+            }
+        }
+
+        match blocks.pop() {
+            Some((_, commands, _)) if blocks.is_empty() => Ok(commands),
+            Some((_, _, opening)) => {
+                let line = script.lines().nth(opening).unwrap_or_default();
+                let error = ParseError::new("Unclosed block", line, line.trim());
+                Err(error.offset_lines(opening))
+            }
+            None => unreachable!(),
+        }
+    }
+}
+
+// The statements of a line of code, without their surrounding whitespace: a
+// block opening ends with its `{`, and a block closing is a lone `}`.
+fn statements(code: &str) -> Vec<&str> {
+    let mut statements = Vec::new();
+    let mut start = 0;
+    for (idx, c) in code.char_indices() {
+        match c {
+            '{' => {
+                statements.push(&code[start..=idx]);
+                start = idx + 1;
+            }
+            '}' => {
+                statements.push(&code[start..idx]);
+                statements.push(&code[idx..=idx]);
+                start = idx + 1;
+            }
+            _ => {}
+        }
+    }
+    statements.push(&code[start..]);
+
+    statements
+        .into_iter()
+        .map(str::trim)
+        .filter(|statement| !statement.is_empty())
+        .collect()
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum EventKind {
+    /// The submarine went above the surface (a negative depth).
+    Surfaced,
+    /// The command overflowed, ending the course.
+    Overflow,
+}
+
+/// Something notable happening on the given command (numbered from 1).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Event {
+    pub step: usize,
+    pub command: Command,
+    pub kind: EventKind,
+}
+
+impl fmt::Display for Event {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let what = match self.kind {
+            EventKind::Surfaced => "surfaced",
+            EventKind::Overflow => "overflow",
+        };
+        write!(f, "Step {} ({}): {}", self.step, self.command, what)
+    }
+}
+
+impl std::error::Error for Event {}
+
+/// The final state after following the `commands`, or the overflow event
+/// ending the course early.
+pub fn follow<N: Navigation>(commands: &[Command]) -> Result<N, Event> {
+    commands
+        .iter()
+        .enumerate()
+        .try_fold(N::default(), |state, (idx, &command)| {
+            state.checked_command(command).ok_or(Event {
+                step: idx + 1,
+                command,
+                kind: EventKind::Overflow,
+            })
+        })
+}
+
+/// Every state of the submarine along the course, starting from the initial
+/// one, with the events on the way.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Trajectory<N> {
+    pub states: Vec<N>,
+    pub commands: Vec<Command>,
+    pub events: Vec<Event>,
+}
+
+impl<N: Navigation> Trajectory<N> {
+    pub fn new(commands: &[Command]) -> Self {
+        let mut trajectory = Trajectory {
+            states: vec![N::default()],
+            commands: Vec::with_capacity(commands.len()),
+            events: Vec::new(),
+        };
+
+        for (idx, &command) in commands.iter().enumerate() {
+            let current = trajectory.last();
+            let event = |kind| Event {
+                step: idx + 1,
+                command,
+                kind,
+            };
+            let next = match current.checked_command(command) {
+                Some(next) => next,
+                None => {
+                    trajectory.events.push(event(EventKind::Overflow));
+                    break;
+                }
+            };
+
+            if next.depth() < 0 && current.depth() >= 0 {
+                trajectory.events.push(event(EventKind::Surfaced));
+            }
+            trajectory.states.push(next);
+            trajectory.commands.push(command);
+        }
+
+        trajectory
+    }
+
+    pub fn last(&self) -> N {
+        self.states[self.states.len() - 1]
+    }
+
+    /// One line per state, the first one being the initial state, without a
+    /// command.
+    pub fn to_csv(&self) -> String {
+        let mut csv = String::from("step,command,distance,depth,aim\n");
+        let commands = std::iter::once(None).chain(self.commands.iter().map(Some));
+        for (step, (state, command)) in self.states.iter().zip(commands).enumerate() {
+            let command = command.map(Command::to_string).unwrap_or_default();
+            csv += &format!(
+                "{},{},{},{},{}\n",
+                step,
+                command,
+                state.distance(),
+                state.depth(),
+                state.aim()
+            );
+        }
+
+        csv
+    }
+
+    /// The course seen from the side, going right and down, with the surface
+    /// as a blue line and the events as red dots.
+    pub fn to_svg(&self) -> String {
+        let points: Vec<(i64, i64)> = self
+            .states
+            .iter()
+            .map(|s| (i64::from(s.distance()), i64::from(s.depth())))
+            .collect();
+        let min_x = points.iter().map(|p| p.0).min().unwrap_or_default();
+        let max_x = points.iter().map(|p| p.0).max().unwrap_or_default();
+        let min_y = points.iter().map(|p| p.1).min().unwrap_or_default().min(0);
+        let max_y = points.iter().map(|p| p.1).max().unwrap_or_default();
+        let (width, height) = ((max_x - min_x).max(1), (max_y - min_y).max(1));
+        let stroke = (width.max(height) as f64 / 500.0).max(0.1);
+
+        let mut svg = format!(
+            "<svg xmlns=\"http://www.w3.org/2000/svg\" viewBox=\"{} {} {} {}\">\n",
+            min_x, min_y, width, height
+        );
+        svg += &format!(
+            "  <line x1=\"{}\" y1=\"0\" x2=\"{}\" y2=\"0\" stroke=\"blue\" stroke-width=\"{:.3}\"/>\n",
+            min_x, max_x, stroke
+        );
+        let polyline: Vec<String> = points.iter().map(|(x, y)| format!("{},{}", x, y)).collect();
+        svg += &format!(
+            "  <polyline points=\"{}\" fill=\"none\" stroke=\"black\" stroke-width=\"{:.3}\"/>\n",
+            polyline.join(" "),
+            stroke
+        );
+        for event in &self.events {
+            // Overflows happen before reaching the next state.
+            let (x, y) = points[event.step.min(points.len() - 1)];
+            svg += &format!(
+                "  <circle cx=\"{}\" cy=\"{}\" r=\"{:.3}\" fill=\"red\"><title>{}</title></circle>\n",
+                x,
+                y,
+                3.0 * stroke,
+                event
+            );
+        }
+        svg += "</svg>\n";
+
+        svg
+    }
+}
+
+pub fn parse(input: &str) -> Result<Vec<Command>, ParseError> {
+    Interpreter::default().parse(input).map_err(|e| e.on_day(2))
+}
+
+pub fn part1(commands: &[Command]) -> Result<i64, Event> {
+    // Calculate the horizontal position and depth you would have after
+    // following the planned course. What do you get if you multiply your final
+    // horizontal position by your final depth?
+    let position: Position = follow(commands)?;
+    Ok(i64::from(position.depth) * i64::from(position.distance))
+}
+
+pub fn part2(commands: &[Command]) -> Result<i64, Event> {
+    // Using this new interpretation of the commands, calculate the horizontal
+    // position and depth you would have after following the planned course.
+    // What do you get if you multiply your final horizontal position by your
+    // final depth?
+    let submarine: Submarine = follow(commands)?;
+    Ok(i64::from(submarine.depth) * i64::from(submarine.distance))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const EXAMPLE: &str = "\
+forward 5
+down 5
+forward 8
+up 3
+down 8
+forward 2";
+
+    #[test]
+    fn part1_example() {
+        assert_eq!(part1(&parse(EXAMPLE).unwrap()).unwrap(), 150);
+    }
+
+    #[test]
+    fn part2_example() {
+        assert_eq!(part2(&parse(EXAMPLE).unwrap()).unwrap(), 900);
+    }
+
+    #[test]
+    fn large_courses() {
+        // The products no longer fit in 32 bits.
+        let commands = parse("forward 100000\ndown 100000").unwrap();
+        assert_eq!(part1(&commands).unwrap(), 10_000_000_000);
+        let commands = parse("down 1000\nforward 1000000").unwrap();
+        assert_eq!(part2(&commands).unwrap(), 1_000_000_000_000_000);
+
+        let commands = parse("down 100000\nforward 100000").unwrap();
+        assert_eq!(
+            part2(&commands).unwrap_err().to_string(),
+            "Step 2 (forward 100000): overflow"
+        );
+        let commands = parse("down 2000000000\ndown 2000000000").unwrap();
+        assert_eq!(part1(&commands).unwrap_err().step, 2);
+    }
+
+    #[test]
+    fn scripts() {
+        let script = "\
+# A comment.
+forward 2  # Another one.
+repeat 2 {
+    down 1
+    repeat 2 {
+        back 1
+    }
+}
+hold 2
+repeat 2 { up 1 }
+";
+        assert_eq!(
+            parse(script).unwrap(),
+            [
+                Command::Forward(2),
+                Command::Down(1),
+                Command::Back(1),
+                Command::Back(1),
+                Command::Down(1),
+                Command::Back(1),
+                Command::Back(1),
+                Command::Hold,
+                Command::Hold,
+                Command::Up(1),
+                Command::Up(1),
+            ]
+        );
+        assert_eq!(
+            parse("repeat 2 { repeat 2 { down 1 } forward 1 }").unwrap(),
+            parse("repeat 2 {\nrepeat 2 {\ndown 1\n}\nforward 1\n}").unwrap()
+        );
+
+        let error = parse("forward 1\nrepeat 2 {\nup 1").unwrap_err();
+        assert_eq!((error.line(), error.message()), (2, "Unclosed block"));
+        let error = parse("forward 1\n}").unwrap_err();
+        assert_eq!((error.line(), error.column()), (2, 1));
+        let error = parse("repeat 2\n}").unwrap_err();
+        assert_eq!((error.line(), error.message()), (1, "Missing {"));
+        let error = parse("forward 1\n  dive 3").unwrap_err();
+        assert_eq!((error.line(), error.column(), error.text()), (2, 3, "dive"));
+        let error = parse("repeat 100000 {\nrepeat 100000 {\nhold 1\n}\n}").unwrap_err();
+        assert_eq!(error.message(), "Too many commands");
+        let error = parse("repeat 9223372036854775809 {\nforward 1\nforward 1\n}").unwrap_err();
+        assert_eq!((error.line(), error.message()), (4, "Too many commands"));
+    }
+
+    #[test]
+    fn custom_command_and_model() {
+        // `dive n`: go down then forward by `n`.
+        struct Dive;
+        impl ScriptCommand for Dive {
+            fn name(&self) -> &str {
+                "dive"
+            }
+
+            fn expand(&self, line: &str, argument: &str) -> Result<Vec<Command>, ParseError> {
+                let n = parse_number(line, argument)?;
+                Ok(vec![Command::Down(n), Command::Forward(n)])
+            }
+        }
+
+        // Goes twice as deep as `Position`.
+        #[derive(Debug, Default, Clone, Copy)]
+        struct Heavy(Position);
+        impl Navigation for Heavy {
+            fn forward(self, d: i32) -> Option<Self> {
+                self.0.forward(d).map(Heavy)
+            }
+
+            fn down(self, d: i32) -> Option<Self> {
+                self.0.down(d.checked_mul(2)?).map(Heavy)
+            }
+
+            fn up(self, d: i32) -> Option<Self> {
+                self.0.up(d).map(Heavy)
+            }
+
+            fn depth(&self) -> i32 {
+                self.0.depth()
+            }
+
+            fn distance(&self) -> i32 {
+                self.0.distance()
+            }
+        }
+
+        let interpreter = Interpreter::default().with_command(Dive);
+        let commands = interpreter.parse("dive 3\nback 1").unwrap();
+        let heavy = commands.iter().fold(Heavy::default(), |h, c| h.command(*c));
+        assert_eq!((heavy.distance(), heavy.depth()), (2, 6));
+    }
+
+    #[test]
+    fn trajectory() {
+        let commands = parse(EXAMPLE).unwrap();
+        let trajectory: Trajectory<Submarine> = Trajectory::new(&commands);
+        assert_eq!(trajectory.states.len(), 7);
+        assert_eq!(
+            (trajectory.last().distance(), trajectory.last().depth()),
+            (15, 60)
+        );
+        assert!(trajectory.events.is_empty());
+
+        let csv = trajectory.to_csv();
+        let lines: Vec<&str> = csv.lines().collect();
+        assert_eq!(lines[0], "step,command,distance,depth,aim");
+        assert_eq!(lines[1], "0,,0,0,0");
+        assert_eq!(lines[3], "2,down 5,5,0,5");
+        assert_eq!(lines[6], "5,down 8,13,40,10");
+        assert!(trajectory
+            .to_svg()
+            .contains("<polyline points=\"0,0 5,0 5,0 13,40"));
+    }
+
+    #[test]
+    fn events() {
+        let commands = parse("down 1\nup 3\ndown 5\nup 4").unwrap();
+        let trajectory: Trajectory<Position> = Trajectory::new(&commands);
+        let surfaced: Vec<usize> = trajectory.events.iter().map(|e| e.step).collect();
+        assert_eq!(surfaced, [2, 4]);
+        assert_eq!(trajectory.events[0].to_string(), "Step 2 (up 3): surfaced");
+
+        let commands = parse("down 2000000000\ndown 2000000000\nforward 1").unwrap();
+        let trajectory: Trajectory<Submarine> = Trajectory::new(&commands);
+        assert_eq!(trajectory.states.len(), 2);
+        assert_eq!(
+            trajectory.events,
+            [Event {
+                step: 2,
+                command: Command::Down(2000000000),
+                kind: EventKind::Overflow,
+            }]
+        );
+    }
+}
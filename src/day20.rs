@@ -0,0 +1,199 @@
+use crate::error::split_blank_line;
+use crate::grid::{Grid, Position};
+use crate::ParseError;
+use std::convert::TryFrom;
+use std::str::FromStr;
+
+const LIGHT_PIXEL: char = '#';
+const DARK_PIXEL: char = '.';
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Pixel {
+    Dark,
+    Light,
+}
+
+impl TryFrom<char> for Pixel {
+    type Error = ParseError;
+
+    fn try_from(c: char) -> Result<Self, Self::Error> {
+        match c {
+            DARK_PIXEL => Ok(Pixel::Dark),
+            LIGHT_PIXEL => Ok(Pixel::Light),
+            _ => {
+                let text = c.to_string();
+                Err(ParseError::new("Invalid pixel", &text, &text))
+            }
+        }
+    }
+}
+
+// Parse a line of pixels, pointing at the offending column on error.
+fn parse_pixels(line: &str) -> Result<Vec<Pixel>, ParseError> {
+    line.chars()
+        .enumerate()
+        .map(|(idx, c)| Pixel::try_from(c).map_err(|e| e.offset_columns(idx)))
+        .collect()
+}
+
+impl From<bool> for Pixel {
+    fn from(b: bool) -> Pixel {
+        if b {
+            Pixel::Light
+        } else {
+            Pixel::Dark
+        }
+    }
+}
+
+impl From<Pixel> for usize {
+    fn from(pixel: Pixel) -> Self {
+        match pixel {
+            Pixel::Light => 1,
+            Pixel::Dark => 0,
+        }
+    }
+}
+
+pub type ImageEnhancementAlgorithm = [Pixel; 512];
+
+#[derive(Debug, Clone)]
+pub struct Image {
+    pixels: Grid<Pixel>,
+    // The pixels "outside" (it's infinite) of the current focus.
+    default_pixel: Pixel,
+}
+
+impl Image {
+    pub fn nbr_lit_pixels(&self) -> usize {
+        self.pixels.iter().filter(|&&p| p == Pixel::Light).count()
+    }
+
+    // The pixel at the given coordinates, which may lie outside of the focus.
+    fn pixel_at(&self, x: isize, y: isize) -> Pixel {
+        match (usize::try_from(x), usize::try_from(y)) {
+            (Ok(x), Ok(y)) => self
+                .pixels
+                .get(Position::new(x, y))
+                .copied()
+                .unwrap_or(self.default_pixel),
+            _ => self.default_pixel,
+        }
+    }
+
+    pub fn enhance(&mut self, algorithm: ImageEnhancementAlgorithm) -> &mut Self {
+        // The focus grows by a pixel on every side, so the pixel at `p` in the
+        // enhanced image is centred on `p - (1, 1)` in the current one.
+        let (width, height) = (self.pixels.width(), self.pixels.height());
+        let pixels = Grid::from_fn(width + 2, height + 2, |p| {
+            let (x, y) = (p.x as isize - 1, p.y as isize - 1);
+            let idx = (y - 1..=y + 1)
+                .flat_map(|y| (x - 1..=x + 1).map(move |x| (x, y)))
+                .fold(0, |acc, (x, y)| {
+                    (acc << 1) + usize::from(self.pixel_at(x, y))
+                });
+            algorithm[idx]
+        });
+
+        self.pixels = pixels;
+        self.default_pixel = if self.default_pixel == Pixel::Light {
+            algorithm[511]
+        } else {
+            algorithm[0]
+        };
+
+        self
+    }
+}
+
+impl FromStr for Image {
+    type Err = ParseError;
+
+    fn from_str(image: &str) -> Result<Self, Self::Err> {
+        Ok(Image {
+            pixels: Grid::parse(image, |c| Pixel::try_from(c).ok())?,
+            default_pixel: Pixel::Dark,
+        })
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct TrenchMap {
+    algorithm: ImageEnhancementAlgorithm,
+    image: Image,
+}
+
+impl TrenchMap {
+    fn nbr_lit_pixels_after(&self, steps: usize) -> usize {
+        let mut image = self.image.clone();
+        for _ in 0..steps {
+            image.enhance(self.algorithm);
+        }
+
+        image.nbr_lit_pixels()
+    }
+}
+
+pub fn parse(input: &str) -> Result<TrenchMap, ParseError> {
+    let (algorithm, image, offset) = split_blank_line(input)
+        .ok_or_else(|| ParseError::missing("Missing input image", input).on_day(20))?;
+    let algorithm = algorithm.trim_end();
+
+    let pixels = parse_pixels(algorithm).map_err(|e| e.on_day(20))?;
+    let len = pixels.len();
+    let algorithm = ImageEnhancementAlgorithm::try_from(pixels).map_err(|_| {
+        let message = format!("Expected 512 pixels, found {}", len);
+        ParseError::new(message, algorithm, algorithm).on_day(20)
+    })?;
+
+    let image: Image = image
+        .parse()
+        .map_err(|e: ParseError| e.offset_lines(offset).on_day(20))?;
+
+    Ok(TrenchMap { algorithm, image })
+}
+
+pub fn part1(trench_map: &TrenchMap) -> usize {
+    // Start with the original input image and apply the image enhancement
+    // algorithm twice, being careful to account for the infinite size of the
+    // images. How many pixels are lit in the resulting image?
+    trench_map.nbr_lit_pixels_after(2)
+}
+
+pub fn part2(trench_map: &TrenchMap) -> usize {
+    // Start again with the original input image and apply the image enhancement
+    // algorithm 50 times. How many pixels are lit in the resulting image?
+    trench_map.nbr_lit_pixels_after(50)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const EXAMPLE: &str = "\
+..#.#..#####.#.#.#.###.##.....###.##.#..###.####..#####..#....#..#..##..###..######.###...####..#..#####..##..#.#####...##.#.#..#.##..#.#......#.###.######.###.####...#.##.##..#..#..#####.....#.#....###..#.##......#.....#..#..#..##..#...##.######.####.####.#.#...#.......#..#.#.#...####.##.#......#..#...##.#.##..#...##.#.##..###.#......#.#.......#.#.#.####.###.##...#.....####.#..#..#.##.#....##..#.####....##...##..#...#......#.#.......#.......##..####..#...#.#.#...##..#.#..###..#####........#..####......#..#
+
+#..#.
+#....
+##..#
+..#..
+..###";
+
+    #[test]
+    fn part1_example() {
+        assert_eq!(part1(&parse(EXAMPLE).unwrap()), 35);
+    }
+
+    #[test]
+    fn part2_example() {
+        assert_eq!(part2(&parse(EXAMPLE).unwrap()), 3351);
+    }
+
+    #[test]
+    fn crlf() {
+        let crlf = EXAMPLE.replace('\n', "\r\n");
+        assert_eq!(part1(&parse(&crlf).unwrap()), 35);
+        let error = parse(&crlf.replacen("\n#..#.", "\n#..x.", 1)).unwrap_err();
+        assert_eq!((error.line(), error.column()), (3, 4));
+    }
+}
@@ -0,0 +1,156 @@
+use crate::error::{parse_lines, parse_number};
+use crate::ParseError;
+use std::str::FromStr;
+
+// Pairs of the sum of three rolls of a three-sided dice with their frequency.
+const DIRAC_DICE: [(u64, u64); 7] = [(3, 1), (4, 3), (5, 6), (6, 7), (7, 6), (8, 3), (9, 1)];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Turn {
+    Player1,
+    Player2,
+}
+
+#[derive(Debug, Clone, Copy)]
+pub struct Player {
+    position: u64,
+    score: u64,
+}
+
+impl FromStr for Player {
+    type Err = ParseError;
+
+    fn from_str(input: &str) -> Result<Self, Self::Err> {
+        let (_, text) = input
+            .split_once("starting position: ")
+            .ok_or_else(|| ParseError::new("Invalid starting position", input, input))?;
+        let position = parse_number(input, text)?;
+        if !(1..=10).contains(&position) {
+            return Err(ParseError::new("Position out of the track", input, text));
+        }
+
+        Ok(Player { position, score: 0 })
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
+pub struct DiracDiceGame {
+    player1: Player,
+    player2: Player,
+}
+
+impl DiracDiceGame {
+    // Use 0 to 9 positions internally to simplify the code. This means the
+    // score added will be (position + 1) instead of position.
+    pub fn new(mut player1: Player, mut player2: Player) -> Self {
+        player1.position -= 1;
+        player2.position -= 1;
+
+        DiracDiceGame { player1, player2 }
+    }
+
+    pub fn deterministic_game(&mut self, winning_score: u64) -> u64 {
+        let mut nbr_rolls = 0;
+        let mut die = (1..=100).cycle();
+
+        loop {
+            nbr_rolls += 3;
+            self.player1.position =
+                (self.player1.position + die.by_ref().take(3).sum::<u64>()) % 10;
+            self.player1.score += self.player1.position + 1;
+            if self.player1.score >= winning_score {
+                break;
+            }
+
+            nbr_rolls += 3;
+            self.player2.position =
+                (self.player2.position + die.by_ref().take(3).sum::<u64>()) % 10;
+            self.player2.score += self.player2.position + 1;
+            if self.player2.score >= winning_score {
+                break;
+            }
+        }
+
+        self.player1.score.min(self.player2.score) * nbr_rolls
+    }
+
+    pub fn dirac_dice_game(&self, winning_score: u64) -> u64 {
+        let mut stack = vec![(*self, Turn::Player1, 1)];
+        let mut player1_wins = 0;
+        let mut player2_wins = 0;
+
+        while let Some((game, turn, freq)) = stack.pop() {
+            for (roll, frequency) in DIRAC_DICE {
+                let mut next_turn = game;
+                match turn {
+                    Turn::Player1 => {
+                        next_turn.player1.position = (game.player1.position + roll) % 10;
+                        next_turn.player1.score += next_turn.player1.position + 1;
+                        if next_turn.player1.score >= winning_score {
+                            player1_wins += freq * frequency;
+                        } else {
+                            stack.push((next_turn, Turn::Player2, freq * frequency));
+                        }
+                    }
+                    Turn::Player2 => {
+                        next_turn.player2.position = (game.player2.position + roll) % 10;
+                        next_turn.player2.score += next_turn.player2.position + 1;
+                        if next_turn.player2.score >= winning_score {
+                            player2_wins += freq * frequency;
+                        } else {
+                            stack.push((next_turn, Turn::Player1, freq * frequency));
+                        }
+                    }
+                }
+            }
+        }
+
+        player1_wins.max(player2_wins)
+    }
+}
+
+pub fn parse(input: &str) -> Result<DiracDiceGame, ParseError> {
+    let players: Vec<Player> =
+        parse_lines(input.trim_end(), str::parse).map_err(|e| e.on_day(21))?;
+    match players[..] {
+        [player1, player2] => Ok(DiracDiceGame::new(player1, player2)),
+        _ => {
+            let message = format!("Expected 2 players, found {}", players.len());
+            Err(ParseError::missing(message, input).on_day(21))
+        }
+    }
+}
+
+pub fn part1(game: &DiracDiceGame) -> u64 {
+    // Play a practice game using the deterministic 100-sided die. The moment
+    // either player wins, what do you get if you multiply the score of the
+    // losing player by the number of times the die was rolled during the game?
+    let mut game = *game;
+    game.deterministic_game(1000)
+}
+
+pub fn part2(game: &DiracDiceGame) -> u64 {
+    // Using your given starting positions, determine every possible outcome.
+    // Find the player that wins in more universes; in how many universes does
+    // that player win?
+    game.dirac_dice_game(21)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const EXAMPLE: &str = "\
+Player 1 starting position: 4
+Player 2 starting position: 8";
+
+    #[test]
+    fn part1_example() {
+        assert_eq!(part1(&parse(EXAMPLE).unwrap()), 739785);
+    }
+
+    #[test]
+    fn part2_example() {
+        assert_eq!(part2(&parse(EXAMPLE).unwrap()), 444356092776315);
+    }
+}
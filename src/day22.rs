@@ -0,0 +1,224 @@
+use crate::error::{parse_lines, parse_number};
+use crate::ParseError;
+use std::str::FromStr;
+
+const INITIALIZATION_AREA: Cuboid = Cuboid {
+    x: (-50, 50),
+    y: (-50, 50),
+    z: (-50, 50),
+};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Cuboid {
+    x: (i64, i64),
+    y: (i64, i64),
+    z: (i64, i64),
+}
+
+impl Cuboid {
+    pub fn volume(&self) -> i64 {
+        (self.x.1 + 1 - self.x.0) * (self.y.1 + 1 - self.y.0) * (self.z.1 + 1 - self.z.0)
+    }
+
+    pub fn intersection(&self, other: &Self) -> Option<Self> {
+        let x = (self.x.0.max(other.x.0), self.x.1.min(other.x.1));
+        let y = (self.y.0.max(other.y.0), self.y.1.min(other.y.1));
+        let z = (self.z.0.max(other.z.0), self.z.1.min(other.z.1));
+        if (x.0 <= x.1) && (y.0 <= y.1) && (z.0 <= z.1) {
+            Some(Cuboid { x, y, z })
+        } else {
+            None
+        }
+    }
+
+    pub fn restrict_to_initialization_area(&self) -> Option<Cuboid> {
+        self.intersection(&INITIALIZATION_AREA)
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
+pub struct RebootStep {
+    state: bool,
+    area: Cuboid,
+}
+
+impl RebootStep {
+    pub fn restrict_to_initialization_area(&self) -> Option<Self> {
+        self.area
+            .restrict_to_initialization_area()
+            .map(|area| RebootStep {
+                state: self.state,
+                area,
+            })
+    }
+}
+
+fn parse_range(line: &str, text: &str, axis: &str) -> Result<(i64, i64), ParseError> {
+    let (min, max) = text
+        .strip_prefix(axis)
+        .and_then(|range| range.split_once(".."))
+        .ok_or_else(|| ParseError::new(format!("Invalid {}range", axis), line, text))?;
+
+    Ok((parse_number(line, min)?, parse_number(line, max)?))
+}
+
+impl FromStr for RebootStep {
+    type Err = ParseError;
+
+    fn from_str(line: &str) -> Result<Self, Self::Err> {
+        let step = line.trim();
+        let (state, ranges) = step
+            .split_once(' ')
+            .ok_or_else(|| ParseError::new("Invalid reboot step", line, step))?;
+        let state = match state {
+            "on" => true,
+            "off" => false,
+            _ => return Err(ParseError::new("Invalid state", line, state)),
+        };
+
+        let mut ranges = ranges.split(',');
+        if let (Some(x), Some(y), Some(z), None) =
+            (ranges.next(), ranges.next(), ranges.next(), ranges.next())
+        {
+            let x = parse_range(line, x, "x=")?;
+            let y = parse_range(line, y, "y=")?;
+            let z = parse_range(line, z, "z=")?;
+            Ok(RebootStep {
+                state,
+                area: Cuboid { x, y, z },
+            })
+        } else {
+            Err(ParseError::new("Expected 3 ranges", line, step))
+        }
+    }
+}
+
+#[derive(Debug, Default, Clone)]
+pub struct Reactor(Vec<(i64, Cuboid)>);
+
+impl Reactor {
+    pub fn nbr_cubes_on(&self) -> i64 {
+        self.0
+            .iter()
+            .map(|(value, cuboid)| value * cuboid.volume())
+            .sum()
+    }
+
+    pub fn apply(&mut self, step: &RebootStep) {
+        // Check against previous cuboids, before inserting the cuboid (only
+        // those that turn on lights though).
+        // If we are turning on cubes:
+        // - a positive cuboid means it was already lit, so insert a negative
+        //   cuboid to compensate.
+        // - a negative cuboid means it was turned off, after having been turned
+        //   on. So we know there is a previous negative cuboid (see above),
+        //   and we reverse the correction.
+        // If we are turning off cubes:
+        // - a positive cuboid means it was lit, so insert a negative cuboid.
+        // - a negative cuboid means it was turned off, after having been turned
+        //   on, so we add a positive cuboid to reverse the correction.
+        //
+        // Afterwards, only insert the lighting cuboids.
+        let mut new_cuboids = self
+            .0
+            .iter()
+            .filter_map(|(turn_on, cuboid)| {
+                cuboid
+                    .intersection(&step.area)
+                    .map(|c| match (step.state, *turn_on == 1) {
+                        (true, true) => (-1, c),
+                        (true, false) => (1, c),
+                        (false, true) => (-1, c),
+                        (false, false) => (1, c),
+                    })
+            })
+            .collect();
+
+        if step.state {
+            self.0.push((1, step.area));
+        }
+
+        // TODO: Prune equal cuboids with opposite signs.
+        self.0.append(&mut new_cuboids);
+    }
+}
+
+pub fn parse(input: &str) -> Result<Vec<RebootStep>, ParseError> {
+    parse_lines(input, str::parse).map_err(|e| e.on_day(22))
+}
+
+pub fn part1(reboot_steps: &[RebootStep]) -> i64 {
+    // Execute the reboot steps. Afterward, considering only cubes in the
+    // region x=-50..50,y=-50..50,z=-50..50, how many cubes are on?
+    let mut reactor = Reactor::default();
+    for step in reboot_steps
+        .iter()
+        .filter_map(RebootStep::restrict_to_initialization_area)
+    {
+        reactor.apply(&step);
+    }
+    reactor.nbr_cubes_on()
+}
+
+pub fn part2(reboot_steps: &[RebootStep]) -> i64 {
+    // Starting again with all cubes off, execute all reboot steps. Afterward,
+    // considering all cubes, how many cubes are on?
+    let mut reactor = Reactor::default();
+    for step in reboot_steps {
+        reactor.apply(step);
+    }
+    reactor.nbr_cubes_on()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const EXAMPLE: &str = "\
+on x=10..12,y=10..12,z=10..12
+on x=11..13,y=11..13,z=11..13
+off x=9..11,y=9..11,z=9..11
+on x=10..10,y=10..10,z=10..10";
+
+    #[test]
+    fn part1_example() {
+        assert_eq!(part1(&parse(EXAMPLE).unwrap()), 39);
+    }
+
+    #[test]
+    fn part2_example() {
+        assert_eq!(part2(&parse(EXAMPLE).unwrap()), 39);
+    }
+
+    fn cuboid(x: (i64, i64), y: (i64, i64), z: (i64, i64)) -> Cuboid {
+        Cuboid { x, y, z }
+    }
+
+    #[test]
+    fn cuboid_intersection() {
+        let a = cuboid((10, 12), (10, 12), (10, 12));
+        let b = cuboid((11, 13), (11, 13), (11, 13));
+        assert_eq!(
+            a.intersection(&b),
+            Some(cuboid((11, 12), (11, 12), (11, 12)))
+        );
+        assert_eq!(b.intersection(&a), a.intersection(&b));
+        assert_eq!(a.intersection(&a), Some(a));
+
+        // Touching on a face.
+        let c = cuboid((12, 20), (0, 10), (12, 12));
+        assert_eq!(
+            a.intersection(&c),
+            Some(cuboid((12, 12), (10, 10), (12, 12)))
+        );
+
+        let d = cuboid((13, 20), (10, 12), (10, 12));
+        assert_eq!(a.intersection(&d), None);
+    }
+
+    #[test]
+    fn cuboid_volume() {
+        assert_eq!(cuboid((10, 12), (10, 12), (10, 12)).volume(), 27);
+        assert_eq!(cuboid((-1, 1), (0, 0), (5, 8)).volume(), 12);
+    }
+}
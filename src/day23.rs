@@ -0,0 +1,347 @@
+use crate::error::char_at;
+use crate::search::{self, Path};
+use crate::ParseError;
+use counter::Counter;
+use std::convert::TryFrom;
+
+pub type Energy = u64;
+
+// The full diagram has two more lines, to be inserted between the first and
+// second lines of the amphipods.
+const UNFOLDED_LINES: [[AmphipodType; 4]; 2] = [
+    [
+        AmphipodType::Desert,
+        AmphipodType::Copper,
+        AmphipodType::Bronze,
+        AmphipodType::Amber,
+    ],
+    [
+        AmphipodType::Desert,
+        AmphipodType::Bronze,
+        AmphipodType::Amber,
+        AmphipodType::Copper,
+    ],
+];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum AmphipodType {
+    Amber,
+    Bronze,
+    Copper,
+    Desert,
+}
+
+impl AmphipodType {
+    const COLOURS: [AmphipodType; 4] = [
+        AmphipodType::Amber,
+        AmphipodType::Bronze,
+        AmphipodType::Copper,
+        AmphipodType::Desert,
+    ];
+
+    pub fn energy(self) -> Energy {
+        match self {
+            AmphipodType::Amber => 1,
+            AmphipodType::Bronze => 10,
+            AmphipodType::Copper => 100,
+            AmphipodType::Desert => 1000,
+        }
+    }
+}
+
+impl TryFrom<char> for AmphipodType {
+    type Error = ParseError;
+
+    fn try_from(c: char) -> Result<Self, Self::Error> {
+        match c {
+            'A' => Ok(AmphipodType::Amber),
+            'B' => Ok(AmphipodType::Bronze),
+            'C' => Ok(AmphipodType::Copper),
+            'D' => Ok(AmphipodType::Desert),
+            _ => {
+                let text = c.to_string();
+                Err(ParseError::new("Invalid amphipod", &text, &text))
+            }
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Room<const ROOM_SIZE: usize> {
+    colour: AmphipodType,
+    spots: [Option<AmphipodType>; ROOM_SIZE],
+}
+
+impl<const ROOM_SIZE: usize> Room<ROOM_SIZE> {
+    pub fn is_filled(&self) -> bool {
+        self.spots.into_iter().all(|c| c == Some(self.colour))
+    }
+
+    pub fn accept(&self, amphipod: AmphipodType) -> Option<(Energy, Self)> {
+        if self.colour == amphipod && self.spots.iter().flatten().all(|&c| c == amphipod) {
+            let p = self
+                .spots
+                .iter()
+                .rposition(Option::is_none)
+                .expect("No more free room.");
+            let mut new_room = *self;
+            new_room.spots[p] = Some(amphipod);
+            Some(((p as Energy + 1) * amphipod.energy(), new_room))
+        } else {
+            None
+        }
+    }
+
+    pub fn take(&self) -> Option<(Energy, AmphipodType, Self)> {
+        // Don't take out an amphipod if it's already in the right room (and not
+        // blocking a different one).
+        if self.spots.iter().flatten().all(|&a| a == self.colour) {
+            None
+        } else {
+            self.spots.iter().position(Option::is_some).map(|p| {
+                let mut new_room = *self;
+                let amphipod = new_room.spots[p].take().unwrap();
+                ((p as Energy + 1) * amphipod.energy(), amphipod, new_room)
+            })
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Situation<const ROOM_SIZE: usize> {
+    hallway: [Option<AmphipodType>; 11],
+    rooms: [Room<ROOM_SIZE>; 4],
+}
+
+impl<const ROOM_SIZE: usize> Situation<ROOM_SIZE> {
+    const POSSIBLE_SPOTS: [usize; 7] = [0, 1, 3, 5, 7, 9, 10];
+    const ROOMS_IDX: [usize; 4] = [2, 4, 6, 8];
+
+    pub fn is_done(&self) -> bool {
+        self.rooms.iter().all(Room::is_filled)
+    }
+
+    fn accessible_rooms(&self, idx: usize) -> impl Iterator<Item = usize> + '_ {
+        let left = (0..idx)
+            .rev()
+            .take_while(|tile| self.hallway[*tile].is_none())
+            .filter(|tile| Self::ROOMS_IDX.contains(tile));
+        let right = (idx + 1..11)
+            .take_while(|tile| self.hallway[*tile].is_none())
+            .filter(|tile| Self::ROOMS_IDX.contains(tile));
+
+        left.chain(right)
+    }
+
+    fn accessible_tiles(&self, idx: usize) -> impl Iterator<Item = usize> + '_ {
+        let left = (0..idx)
+            .rev()
+            .take_while(|tile| self.hallway[*tile].is_none())
+            .filter(|tile| Self::POSSIBLE_SPOTS.contains(tile));
+        let right = (idx + 1..11)
+            .take_while(|tile| self.hallway[*tile].is_none())
+            .filter(|tile| Self::POSSIBLE_SPOTS.contains(tile));
+
+        left.chain(right)
+    }
+
+    pub fn next_moves(&self) -> Vec<(Energy, Self)> {
+        // If we can move an amphipod into the correct room, this is the optimal
+        // move.
+        let mut result = Vec::new();
+        for idx in 0..self.hallway.len() {
+            if let Some(amphipod) = self.hallway[idx] {
+                for room_idx in self.accessible_rooms(idx) {
+                    if let Some((energy, room)) = self.rooms[room_idx / 2 - 1].accept(amphipod) {
+                        let mut new_sit = *self;
+                        new_sit.hallway[idx].take();
+                        new_sit.rooms[room_idx / 2 - 1] = room;
+                        let cost = room_idx.abs_diff(idx) as Energy * amphipod.energy();
+                        result.push((energy + cost, new_sit));
+                    }
+                }
+            }
+        }
+
+        if !result.is_empty() {
+            return result;
+        }
+
+        // Otherwise, for each room, try to take one amphipod out and spread it
+        // over the possible tiles.
+        for (room_idx, room) in Self::ROOMS_IDX.into_iter().zip(self.rooms) {
+            if let Some((energy, amphipod, room)) = room.take() {
+                for idx in self.accessible_tiles(room_idx) {
+                    let mut new_sit = *self;
+                    new_sit.hallway[idx] = Some(amphipod);
+                    new_sit.rooms[room_idx / 2 - 1] = room;
+                    let cost = room_idx.abs_diff(idx) as Energy * amphipod.energy();
+                    result.push((energy + cost, new_sit));
+                }
+            }
+        }
+
+        result
+    }
+}
+
+impl Situation<2> {
+    fn unfold(&self) -> Situation<4> {
+        let rooms = std::array::from_fn(|idx| {
+            let room = self.rooms[idx];
+            Room {
+                colour: room.colour,
+                spots: [
+                    room.spots[0],
+                    Some(UNFOLDED_LINES[0][idx]),
+                    Some(UNFOLDED_LINES[1][idx]),
+                    room.spots[1],
+                ],
+            }
+        });
+
+        Situation {
+            hallway: self.hallway,
+            rooms,
+        }
+    }
+}
+
+// The cheapest sequence of moves organizing the amphipods, if any.
+pub fn solve<const ROOM_SIZE: usize>(
+    situation: Situation<ROOM_SIZE>,
+) -> Option<Path<Situation<ROOM_SIZE>, Energy>> {
+    search::dijkstra(
+        situation,
+        |current| {
+            current
+                .next_moves()
+                .into_iter()
+                .map(|(energy, next)| (next, energy))
+        },
+        Situation::is_done,
+    )
+}
+
+fn parse_rooms_line(line: &str) -> Result<[AmphipodType; 4], ParseError> {
+    let amphipods = line
+        .char_indices()
+        .filter(|(_, c)| !matches!(c, '#' | ' '))
+        .map(|(idx, c)| {
+            AmphipodType::try_from(c)
+                .map_err(|_| ParseError::new("Invalid amphipod", line, char_at(line, idx)))
+        })
+        .collect::<Result<Vec<_>, _>>()?;
+    let len = amphipods.len();
+
+    <[AmphipodType; 4]>::try_from(amphipods).map_err(|_| {
+        let message = format!("Expected 4 amphipods, found {}", len);
+        ParseError::new(message, line, line)
+    })
+}
+
+pub fn parse(input: &str) -> Result<Situation<2>, ParseError> {
+    // Skip the wall and the (empty) hallway, then read the amphipods from the
+    // first and second lines of the rooms.
+    let mut lines = input
+        .lines()
+        .enumerate()
+        .skip(2)
+        .map(|(idx, line)| parse_rooms_line(line).map_err(|e| e.offset_lines(idx).on_day(23)));
+    let missing = || ParseError::missing("Missing rooms", input.trim_end()).on_day(23);
+
+    let first = lines.next().ok_or_else(missing)??;
+    let second = lines.next().ok_or_else(missing)??;
+
+    // Otherwise, the amphipods could never all be organized.
+    let amphipods: Counter<AmphipodType> = first.iter().chain(second.iter()).copied().collect();
+    if let Some(colour) = AmphipodType::COLOURS.iter().find(|c| amphipods[c] != 2) {
+        let message = format!("Expected 2 amphipods of type {:?}", colour);
+        return Err(ParseError::new(message, "", "").offset_lines(3).on_day(23));
+    }
+
+    let rooms = std::array::from_fn(|idx| Room {
+        colour: AmphipodType::COLOURS[idx],
+        spots: [Some(first[idx]), Some(second[idx])],
+    });
+
+    Ok(Situation {
+        hallway: [None; 11],
+        rooms,
+    })
+}
+
+pub fn part1(situation: &Situation<2>) -> Energy {
+    // What is the least energy required to organize the amphipods?
+    solve(*situation).unwrap().cost
+}
+
+pub fn part2(situation: &Situation<2>) -> Energy {
+    // Using the initial configuration from the full diagram, what is the least
+    // energy required to organize the amphipods?
+    solve(situation.unfold()).unwrap().cost
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const EXAMPLE: &str = "\
+#############
+#...........#
+###B#C#B#D###
+  #A#D#C#A#
+  #########";
+
+    #[test]
+    fn part1_example() {
+        assert_eq!(part1(&parse(EXAMPLE).unwrap()), 12521);
+    }
+
+    #[test]
+    fn part2_example() {
+        assert_eq!(part2(&parse(EXAMPLE).unwrap()), 44169);
+    }
+
+    use AmphipodType::*;
+
+    fn room(colour: AmphipodType, spots: [Option<AmphipodType>; 2]) -> Room<2> {
+        Room { colour, spots }
+    }
+
+    #[test]
+    fn room_accept() {
+        // The deepest free spot is used.
+        assert_eq!(
+            room(Amber, [None, None]).accept(Amber),
+            Some((2, room(Amber, [None, Some(Amber)])))
+        );
+        assert_eq!(
+            room(Bronze, [None, Some(Bronze)]).accept(Bronze),
+            Some((10, room(Bronze, [Some(Bronze), Some(Bronze)])))
+        );
+        // Not the room of this amphipod, or another amphipod still inside.
+        assert_eq!(room(Amber, [None, None]).accept(Bronze), None);
+        assert_eq!(room(Amber, [None, Some(Copper)]).accept(Amber), None);
+    }
+
+    #[test]
+    fn room_take() {
+        assert_eq!(
+            room(Amber, [Some(Desert), Some(Amber)]).take(),
+            Some((1000, Desert, room(Amber, [None, Some(Amber)])))
+        );
+        // Amphipods blocking another one must go out.
+        assert_eq!(
+            room(Amber, [None, Some(Copper)]).take(),
+            Some((200, Copper, room(Amber, [None, None])))
+        );
+        assert_eq!(
+            room(Amber, [Some(Amber), Some(Bronze)]).take(),
+            Some((1, Amber, room(Amber, [None, Some(Bronze)])))
+        );
+        // Nothing to move.
+        assert_eq!(room(Amber, [None, Some(Amber)]).take(), None);
+        assert_eq!(room(Amber, [None, None]).take(), None);
+    }
+}
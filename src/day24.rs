@@ -0,0 +1,271 @@
+use crate::error::parse_lines;
+use crate::ParseError;
+use std::str::FromStr;
+
+// MONAD handles each digit of the model number with a block of 18 instructions.
+const BLOCK_LENGTH: usize = 18;
+
+pub type Value = i64;
+pub type Program = Vec<Instruction>;
+
+#[derive(Debug, Clone, Copy)]
+pub enum Register {
+    W,
+    X,
+    Y,
+    Z,
+}
+
+impl FromStr for Register {
+    type Err = ParseError;
+
+    fn from_str(register: &str) -> Result<Self, Self::Err> {
+        Ok(match register {
+            "w" => Register::W,
+            "x" => Register::X,
+            "y" => Register::Y,
+            "z" => Register::Z,
+            _ => return Err(ParseError::new("Invalid register", register, register)),
+        })
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
+pub enum Argument {
+    Register(Register),
+    Value(Value),
+}
+
+impl FromStr for Argument {
+    type Err = ParseError;
+
+    fn from_str(argument: &str) -> Result<Self, Self::Err> {
+        if let Ok(r) = argument.parse::<Register>() {
+            Ok(Argument::Register(r))
+        } else if let Ok(v) = argument.parse::<Value>() {
+            Ok(Argument::Value(v))
+        } else {
+            Err(ParseError::new("Invalid argument", argument, argument))
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
+pub enum Instruction {
+    Input(Register),
+    Add(Register, Argument),
+    Multiply(Register, Argument),
+    Divide(Register, Argument),
+    Modulo(Register, Argument),
+    Equal(Register, Argument),
+}
+
+impl FromStr for Instruction {
+    type Err = ParseError;
+
+    fn from_str(instruction: &str) -> Result<Self, Self::Err> {
+        // Point at the operand within the whole instruction.
+        fn operand<T: FromStr<Err = ParseError>>(
+            instruction: &str,
+            o: &str,
+        ) -> Result<T, ParseError> {
+            o.parse()
+                .map_err(|e: ParseError| ParseError::new(e.message(), instruction, o))
+        }
+        let r = |r| operand(instruction, r);
+        let a = |a| operand(instruction, a);
+
+        let mut iter = instruction.split_whitespace();
+        Ok(match (iter.next(), iter.next(), iter.next(), iter.next()) {
+            (Some("inp"), Some(x), None, None) => Instruction::Input(r(x)?),
+            (Some("add"), Some(x), Some(y), None) => Instruction::Add(r(x)?, a(y)?),
+            (Some("mul"), Some(x), Some(y), None) => Instruction::Multiply(r(x)?, a(y)?),
+            (Some("div"), Some(x), Some(y), None) => Instruction::Divide(r(x)?, a(y)?),
+            (Some("mod"), Some(x), Some(y), None) => Instruction::Modulo(r(x)?, a(y)?),
+            (Some("eql"), Some(x), Some(y), None) => Instruction::Equal(r(x)?, a(y)?),
+            _ => {
+                return Err(ParseError::new(
+                    "Invalid instruction",
+                    instruction,
+                    instruction,
+                ))
+            }
+        })
+    }
+}
+
+#[allow(clippy::upper_case_acronyms)]
+#[derive(Debug, Clone, Copy)]
+pub struct ALU<'a> {
+    program: &'a Program,
+    w: Value,
+    x: Value,
+    y: Value,
+    z: Value,
+}
+
+impl<'a> ALU<'a> {
+    #[allow(clippy::ptr_arg)]
+    pub fn new(program: &'a Program) -> Self {
+        ALU {
+            program,
+            w: 0,
+            x: 0,
+            y: 0,
+            z: 0,
+        }
+    }
+
+    pub fn run(&mut self, mut input: impl Iterator<Item = Value>) -> Value {
+        for &instruction in self.program {
+            match instruction {
+                Instruction::Input(r) => *self.get_register_mut(r) = input.next().unwrap(),
+                Instruction::Add(r, a) => *self.get_register_mut(r) += self.get_value(a),
+                Instruction::Multiply(r, a) => *self.get_register_mut(r) *= self.get_value(a),
+                Instruction::Divide(r, a) => *self.get_register_mut(r) /= self.get_value(a),
+                Instruction::Modulo(r, a) => *self.get_register_mut(r) %= self.get_value(a),
+                Instruction::Equal(r, a) => {
+                    *self.get_register_mut(r) =
+                        Value::from(self.get_register(r) == self.get_value(a))
+                }
+            }
+        }
+
+        self.z
+    }
+
+    fn get_value(&self, argument: Argument) -> Value {
+        match argument {
+            Argument::Register(r) => self.get_register(r),
+            Argument::Value(v) => v,
+        }
+    }
+
+    fn get_register(&self, register: Register) -> Value {
+        match register {
+            Register::W => self.w,
+            Register::X => self.x,
+            Register::Y => self.y,
+            Register::Z => self.z,
+        }
+    }
+
+    fn get_register_mut(&mut self, register: Register) -> &mut Value {
+        match register {
+            Register::W => &mut self.w,
+            Register::X => &mut self.x,
+            Register::Y => &mut self.y,
+            Register::Z => &mut self.z,
+        }
+    }
+}
+
+// Essentially, we have fourteen steps, of which 7 will multiply z by 26 and add
+// (input + something), and 7 of which will divide by 26, when the input is equal
+// to an offset previous input. Pairing them like a stack gives the constraints
+// `digits[second] = digits[first] + offset`, from which we pick the largest (or
+// smallest) digits.
+fn model_number(program: &Program, largest: bool) -> Option<Value> {
+    let mut stack = Vec::new();
+    let mut constraints = Vec::new();
+    for (idx, block) in program.chunks(BLOCK_LENGTH).enumerate() {
+        match (block.get(4), block.get(5), block.get(15)) {
+            (
+                Some(Instruction::Divide(Register::Z, Argument::Value(1))),
+                _,
+                Some(Instruction::Add(Register::Y, Argument::Value(offset))),
+            ) => stack.push((idx, *offset)),
+            (
+                Some(Instruction::Divide(Register::Z, Argument::Value(26))),
+                Some(Instruction::Add(Register::X, Argument::Value(check))),
+                _,
+            ) => {
+                let (first, offset) = stack.pop()?;
+                constraints.push((first, idx, offset + check));
+            }
+            _ => return None,
+        }
+    }
+
+    let mut digits = vec![0; program.len() / BLOCK_LENGTH];
+    for (first, second, offset) in constraints {
+        digits[first] = if largest {
+            9.min(9 - offset)
+        } else {
+            1.max(1 - offset)
+        };
+        digits[second] = digits[first] + offset;
+    }
+
+    if digits.iter().all(|d| (1..=9).contains(d))
+        && ALU::new(program).run(digits.iter().copied()) == 0
+    {
+        Some(digits.iter().fold(0, |acc, d| acc * 10 + d))
+    } else {
+        None
+    }
+}
+
+pub fn parse(input: &str) -> Result<Program, ParseError> {
+    parse_lines(input, str::parse).map_err(|e| e.on_day(24))
+}
+
+pub fn part1(program: &Program) -> Value {
+    // To enable as many submarine features as possible, find the largest valid
+    // fourteen-digit model number that contains no 0 digits. What is the
+    // largest model number accepted by MONAD?
+    model_number(program, true).expect("No model number accepted by MONAD.")
+}
+
+pub fn part2(program: &Program) -> Value {
+    // What is the smallest model number accepted by MONAD?
+    model_number(program, false).expect("No model number accepted by MONAD.")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // The (`div z`, `add x`, `add y`) arguments of each block of a MONAD
+    // program.
+    const BLOCKS: [(Value, Value, Value); 14] = [
+        (1, 15, 8),
+        (1, 15, 11),
+        (1, 13, 0),
+        (26, -1, 1),
+        (1, 10, 5),
+        (26, -2, 15),
+        (1, 13, 7),
+        (26, -12, 7),
+        (1, 15, 0),
+        (1, 13, 6),
+        (26, -6, 5),
+        (26, 4, 5),
+        (26, -17, 4),
+        (26, -2, 4),
+    ];
+
+    fn monad() -> String {
+        BLOCKS
+            .iter()
+            .map(|(div, add_x, add_y)| {
+                format!(
+                    "inp w\nmul x 0\nadd x z\nmod x 26\ndiv z {}\nadd x {}\neql x w\neql x 0\n\
+                     mul y 0\nadd y 25\nmul y x\nadd y 1\nmul z y\nmul y 0\nadd y w\nadd y {}\n\
+                     mul y x\nadd z y\n",
+                    div, add_x, add_y
+                )
+            })
+            .collect()
+    }
+
+    #[test]
+    fn part1_example() {
+        assert_eq!(part1(&parse(&monad()).unwrap()), 39986994599939);
+    }
+
+    #[test]
+    fn part2_example() {
+        assert_eq!(part2(&parse(&monad()).unwrap()), 17211461111517);
+    }
+}
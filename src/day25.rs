@@ -0,0 +1,126 @@
+use crate::grid::{Edges, Grid, Position};
+use crate::ParseError;
+use std::convert::TryFrom;
+use std::str::FromStr;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Location {
+    Empty,
+    East,
+    South,
+}
+
+impl TryFrom<char> for Location {
+    type Error = ParseError;
+
+    fn try_from(c: char) -> Result<Self, Self::Error> {
+        match c {
+            '.' => Ok(Location::Empty),
+            '>' => Ok(Location::East),
+            'v' => Ok(Location::South),
+            _ => {
+                let text = c.to_string();
+                Err(ParseError::new("Invalid location", &text, &text))
+            }
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SeaFloor(Grid<Location>);
+
+impl SeaFloor {
+    // The sea cucumbers leaving one edge of the map reappear on the opposite
+    // one.
+    fn adjacent(&self, p: Position, dx: isize, dy: isize) -> Location {
+        self.0
+            .neighbour(p, dx, dy)
+            .map_or(Location::Empty, |p| self.0[p])
+    }
+
+    fn north(&self, p: Position) -> Location {
+        self.adjacent(p, 0, -1)
+    }
+
+    fn south(&self, p: Position) -> Location {
+        self.adjacent(p, 0, 1)
+    }
+
+    fn west(&self, p: Position) -> Location {
+        self.adjacent(p, -1, 0)
+    }
+
+    fn east(&self, p: Position) -> Location {
+        self.adjacent(p, 1, 0)
+    }
+}
+
+impl FromStr for SeaFloor {
+    type Err = ParseError;
+
+    fn from_str(input: &str) -> Result<Self, Self::Err> {
+        let map = Grid::parse(input, |c| Location::try_from(c).ok())?;
+        Ok(SeaFloor(map.with_edges(Edges::Wrapping)))
+    }
+}
+
+impl Iterator for SeaFloor {
+    type Item = ();
+
+    fn next(&mut self) -> Option<Self::Item> {
+        // Move the east-facing sea cucumbers.
+        let east = SeaFloor(self.0.map_with_position(|p, l| match l {
+            Location::Empty if self.west(p) == Location::East => Location::East,
+            Location::East if self.east(p) == Location::Empty => Location::Empty,
+            l => *l,
+        }));
+
+        // Move the south-facing sea cucumbers, keeping the intermediate map to
+        // compare, returning `None` if there hasn't been a change.
+        let south = east.0.map_with_position(|p, l| match l {
+            Location::Empty if east.north(p) == Location::South => Location::South,
+            Location::South if east.south(p) == Location::Empty => Location::Empty,
+            l => *l,
+        });
+
+        let moved = south != self.0;
+        self.0 = south;
+
+        if moved {
+            Some(())
+        } else {
+            None
+        }
+    }
+}
+
+pub fn parse(input: &str) -> Result<SeaFloor, ParseError> {
+    input.parse().map_err(|e: ParseError| e.on_day(25))
+}
+
+pub fn part1(sea_floor: &SeaFloor) -> usize {
+    // Find somewhere safe to land your submarine. What is the first step on
+    // which no sea cucumbers move?
+    sea_floor.clone().count() + 1
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const EXAMPLE: &str = "\
+v...>>.vv>
+.vv>>.vv..
+>>.>v>...v
+>>v>>.>.v.
+v>v.vv.v..
+>.>>..v...
+.vv..>.>v.
+v.v..>>v.v
+....v..v.>";
+
+    #[test]
+    fn part1_example() {
+        assert_eq!(part1(&parse(EXAMPLE).unwrap()), 58);
+    }
+}
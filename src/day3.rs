@@ -0,0 +1,208 @@
+use crate::error::{char_at, parse_lines};
+use crate::ParseError;
+use num_bigint::BigUint;
+use std::str::FromStr;
+
+// Widest values supported.
+const MAX_WIDTH: usize = u128::BITS as usize;
+
+#[derive(Debug, Default, Clone)]
+pub struct DiagnosticReport {
+    values: Vec<u128>,
+    // Number of bits of each value, up to 128.
+    width: usize,
+}
+
+impl DiagnosticReport {
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    pub fn gamma_rate(&self) -> u128 {
+        (0..self.width)
+            .rev()
+            .map(|idx| {
+                let mask = 1_u128 << idx;
+                if self.values.iter().filter(|n| *n & mask > 0).count() > self.values.len() / 2 {
+                    mask
+                } else {
+                    0
+                }
+            })
+            .sum()
+    }
+
+    pub fn epsilon_rate(&self) -> u128 {
+        let all_bits = u128::MAX.checked_shr((MAX_WIDTH - self.width) as u32);
+        self.gamma_rate() ^ all_bits.unwrap_or_default()
+    }
+
+    /// The product of the rates, which may need up to 256 bits.
+    pub fn power_consumption(&self) -> BigUint {
+        BigUint::from(self.gamma_rate()) * self.epsilon_rate()
+    }
+
+    pub fn oxygen_generator_rating(&self) -> u128 {
+        // Keep the larger slice at each step, breaking ties by taking 1.
+        self.rating(|zeros, ones| ones.len() >= zeros.len())
+    }
+
+    pub fn co2_scrubber_rating(&self) -> u128 {
+        // Keep the smaller slice at each step, breaking ties by taking 0.
+        self.rating(|zeros, ones| zeros.len() > ones.len())
+    }
+
+    // Keep splitting along the 0/1 axis, from the most significant bit, keeping
+    // the ones if `keep_ones` says so, until we have only one remaining value.
+    fn rating(&self, keep_ones: impl Fn(&[u128], &[u128]) -> bool) -> u128 {
+        let mut values = self.values.clone();
+        // The values MUST be sorted for this to work.
+        values.sort_unstable();
+        let mut remaining = values.as_slice();
+
+        for idx in (0..self.width).rev() {
+            if remaining.len() <= 1 {
+                break;
+            }
+
+            let mask = 1_u128 << idx;
+            match remaining.iter().position(|n| *n & mask == mask) {
+                None | Some(0) => (),
+                Some(idx) => {
+                    let (zeros, ones) = remaining.split_at(idx);
+                    remaining = if keep_ones(zeros, ones) { ones } else { zeros };
+                }
+            }
+        }
+
+        remaining[0]
+    }
+
+    pub fn life_support_rating(&self) -> BigUint {
+        BigUint::from(self.oxygen_generator_rating()) * self.co2_scrubber_rating()
+    }
+}
+
+impl FromStr for DiagnosticReport {
+    type Err = ParseError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        // All the values have the width of the first one.
+        let width = s.lines().next().map_or(0, |line| line.trim_end().len());
+        let values = parse_lines(s, |line| {
+            let line = line.trim_end();
+            if let Some(idx) = line.find(|c| !matches!(c, '0' | '1')) {
+                Err(ParseError::new("Invalid bit", line, char_at(line, idx)))
+            } else if line.len() != width {
+                let message = format!("Expected {} bits, found {}", width, line.len());
+                Err(ParseError::new(message, line, line))
+            } else if width > MAX_WIDTH {
+                let message = format!("Expected at most {} bits, found {}", MAX_WIDTH, width);
+                Err(ParseError::new(message, line, line))
+            } else {
+                u128::from_str_radix(line, 2)
+                    .map_err(|_| ParseError::new("Invalid binary number", line, line))
+            }
+        })?;
+
+        if values.is_empty() {
+            return Err(ParseError::missing("Missing values", ""));
+        }
+
+        Ok(DiagnosticReport { values, width })
+    }
+}
+
+pub fn parse(input: &str) -> Result<DiagnosticReport, ParseError> {
+    input.parse().map_err(|e: ParseError| e.on_day(3))
+}
+
+pub fn part1(report: &DiagnosticReport) -> BigUint {
+    // Use the binary numbers in your diagnostic report to calculate the gamma
+    // rate and epsilon rate, then multiply them together. What is the power
+    // consumption of the submarine? (Be sure to represent your answer in
+    // decimal, not binary.)
+    report.power_consumption()
+}
+
+pub fn part2(report: &DiagnosticReport) -> BigUint {
+    // Use the binary numbers in your diagnostic report to calculate the oxygen
+    // generator rating and CO2 scrubber rating, then multiply them together.
+    // What is the life support rating of the submarine? (Be sure to represent
+    // your answer in decimal, not binary.)
+    report.life_support_rating()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const EXAMPLE: &str = "\
+00100
+11110
+10110
+10111
+10101
+01111
+00111
+11100
+10000
+11001
+00010
+01010";
+
+    #[test]
+    fn part1_example() {
+        assert_eq!(part1(&parse(EXAMPLE).unwrap()).to_string(), "198");
+    }
+
+    #[test]
+    fn part2_example() {
+        assert_eq!(part2(&parse(EXAMPLE).unwrap()).to_string(), "230");
+    }
+
+    #[test]
+    fn rates() {
+        let report = parse(EXAMPLE).unwrap();
+        assert_eq!(report.gamma_rate(), 22);
+        assert_eq!(report.epsilon_rate(), 9);
+        assert_eq!(report.oxygen_generator_rating(), 23);
+        assert_eq!(report.co2_scrubber_rating(), 10);
+    }
+
+    #[test]
+    fn widths() {
+        // Forty bits: the example, shifted left by 35 bits and with ones
+        // below.
+        let wide: String = EXAMPLE
+            .lines()
+            .map(|line| format!("{}{}\n", line, "1".repeat(35)))
+            .collect();
+        let report = parse(&wide).unwrap();
+        assert_eq!(report.width(), 40);
+        assert_eq!(report.gamma_rate(), (22 << 35) + (1 << 35) - 1);
+        assert_eq!(report.epsilon_rate(), 9 << 35);
+        assert_eq!(report.oxygen_generator_rating(), (23 << 35) + (1 << 35) - 1);
+
+        // 128 bits, with a product needing 256 bits.
+        let ones = "1".repeat(128);
+        let mixed = format!("{}{}", "1".repeat(64), "0".repeat(64));
+        let report = parse(&format!("{}\n{}\n{}", ones, ones, mixed)).unwrap();
+        assert_eq!(report.gamma_rate(), u128::MAX);
+        assert_eq!(report.epsilon_rate(), 0);
+        assert_eq!(report.co2_scrubber_rating(), u128::MAX << 64);
+        assert_eq!(part1(&report), BigUint::ZERO);
+        assert_eq!(
+            part2(&report).to_string(),
+            "115792089237316195417293883273301227089093912875511959159910300700091036467200"
+        );
+
+        let error = parse(&"1".repeat(129)).unwrap_err();
+        assert_eq!(error.message(), "Expected at most 128 bits, found 129");
+        let error = parse("0101\n010").unwrap_err();
+        assert_eq!(
+            (error.line(), error.message()),
+            (2, "Expected 4 bits, found 3")
+        );
+    }
+}
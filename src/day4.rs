@@ -0,0 +1,381 @@
+use crate::error::parse_number;
+use crate::grid::{Grid, Position};
+use crate::ParseError;
+use std::str::FromStr;
+
+/// A way of winning, by marking all the numbers of a line or of a set of
+/// positions of the card.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Pattern {
+    Rows,
+    Columns,
+    /// Both diagonals, on square cards only.
+    Diagonals,
+    Corners,
+    Full,
+    /// Positions from the top left corner. Cards not containing all of them
+    /// cannot win with it.
+    Mask(Vec<Position>),
+}
+
+impl Pattern {
+    /// The patterns of the puzzle: complete rows and columns.
+    pub const STANDARD: [Pattern; 2] = [Pattern::Rows, Pattern::Columns];
+
+    /// A mask drawn with `#` for the positions to mark, and `.` for the
+    /// others.
+    pub fn mask(drawing: &str) -> Result<Self, ParseError> {
+        let grid = Grid::parse(drawing, |c| match c {
+            '#' => Some(true),
+            '.' => Some(false),
+            _ => None,
+        })?;
+
+        Ok(Pattern::Mask(
+            grid.enumerate()
+                .filter_map(|(p, marked)| if *marked { Some(p) } else { None })
+                .collect(),
+        ))
+    }
+
+    pub fn matches(&self, marks: &Grid<bool>) -> bool {
+        let (width, height) = (marks.width(), marks.height());
+        let marked = |x, y| marks.get(Position::new(x, y)).copied().unwrap_or_default();
+
+        match self {
+            Pattern::Rows => marks.rows().any(|row| row.iter().all(|m| *m)),
+            Pattern::Columns => marks.columns().any(|mut column| column.all(|m| *m)),
+            Pattern::Diagonals => {
+                width == height
+                    && ((0..width).all(|i| marked(i, i))
+                        || (0..width).all(|i| marked(width - 1 - i, i)))
+            }
+            Pattern::Corners => {
+                !marks.is_empty()
+                    && [
+                        (0, 0),
+                        (width - 1, 0),
+                        (0, height - 1),
+                        (width - 1, height - 1),
+                    ]
+                    .into_iter()
+                    .all(|(x, y)| marked(x, y))
+            }
+            Pattern::Full => marks.iter().all(|m| *m),
+            Pattern::Mask(positions) => positions.iter().all(|p| marked(p.x, p.y)),
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct BingoCard {
+    numbers: Grid<u32>,
+    marks: Grid<bool>,
+}
+
+impl BingoCard {
+    pub fn new(numbers: Grid<u32>) -> Self {
+        let marks = Grid::new(numbers.width(), numbers.height(), false);
+        BingoCard { numbers, marks }
+    }
+
+    /// Returns whether the card holds the number.
+    pub fn mark(&mut self, draw: u32) -> bool {
+        let positions: Vec<Position> = self
+            .numbers
+            .enumerate()
+            .filter_map(|(p, v)| if *v == draw { Some(p) } else { None })
+            .collect();
+        for &p in &positions {
+            self.marks[p] = true;
+        }
+
+        !positions.is_empty()
+    }
+
+    pub fn win(&self, patterns: &[Pattern]) -> bool {
+        patterns.iter().any(|pattern| pattern.matches(&self.marks))
+    }
+
+    /// The sum of the numbers not marked.
+    pub fn score(&self) -> u32 {
+        self.numbers
+            .iter()
+            .zip(self.marks.iter())
+            .filter_map(|(v, m)| if *m { None } else { Some(v) })
+            .sum()
+    }
+}
+
+impl FromStr for BingoCard {
+    type Err = ParseError;
+
+    // One row of numbers per line.
+    fn from_str(input: &str) -> Result<Self, Self::Err> {
+        let mut rows: Vec<Vec<u32>> = Vec::new();
+        for (idy, line) in input.lines().enumerate() {
+            let row = line
+                .split_whitespace()
+                .map(|c| parse_number(line, c))
+                .collect::<Result<Vec<u32>, _>>()
+                .map_err(|e| e.offset_lines(idy))?;
+            if let Some(first) = rows.first().filter(|first| first.len() != row.len()) {
+                let message = format!("Expected {} numbers, found {}", first.len(), row.len());
+                return Err(ParseError::new(message, line, line.trim()).offset_lines(idy));
+            }
+            rows.push(row);
+        }
+
+        match Grid::from_rows(rows) {
+            Some(numbers) if !numbers.is_empty() => Ok(BingoCard::new(numbers)),
+            _ => Err(ParseError::missing("Missing numbers", input)),
+        }
+    }
+}
+
+/// A card winning, on the given turn (counted from 0) and draw. The score is
+/// that of the puzzle: the sum of the unmarked numbers times the draw.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Win {
+    pub card: usize,
+    pub turn: usize,
+    pub draw: u32,
+    pub score: u32,
+}
+
+#[derive(Debug, Clone)]
+pub struct Bingo {
+    draws: Vec<u32>,
+    cards: Vec<BingoCard>,
+}
+
+impl Bingo {
+    pub fn new(draws: Vec<u32>, cards: Vec<BingoCard>) -> Self {
+        Bingo { draws, cards }
+    }
+
+    pub fn draws(&self) -> &[u32] {
+        &self.draws
+    }
+
+    pub fn cards(&self) -> &[BingoCard] {
+        &self.cards
+    }
+
+    /// Play until every card has won or the draws run out. Each card wins
+    /// once; cards winning on the same draw are listed in order.
+    pub fn play(&self, patterns: &[Pattern]) -> Vec<Win> {
+        let mut cards: Vec<(usize, BingoCard)> = self.cards.iter().cloned().enumerate().collect();
+        let mut wins = Vec::new();
+
+        for (turn, &draw) in self.draws.iter().enumerate() {
+            cards.retain_mut(|(idx, card)| {
+                if card.mark(draw) && card.win(patterns) {
+                    wins.push(Win {
+                        card: *idx,
+                        turn,
+                        draw,
+                        score: card.score() * draw,
+                    });
+                    false
+                } else {
+                    true
+                }
+            });
+
+            if cards.is_empty() {
+                break;
+            }
+        }
+
+        wins
+    }
+}
+
+pub fn parse(input: &str) -> Result<Bingo, ParseError> {
+    let mut lines = input.lines().enumerate();
+    let (idx, line) = lines
+        .find(|(_, line)| !line.trim().is_empty())
+        .ok_or_else(|| ParseError::missing("Missing draws", input).on_day(4))?;
+    let draws = line
+        .split(',')
+        .map(|v| parse_number(line, v.trim()))
+        .collect::<Result<Vec<u32>, _>>()
+        .map_err(|e| e.offset_lines(idx).on_day(4))?;
+
+    // The cards are separated by blank lines.
+    let mut cards = Vec::new();
+    let mut block: Vec<&str> = Vec::new();
+    let mut start = 0;
+    for (idx, line) in lines.chain(std::iter::once((0, ""))) {
+        if line.trim().is_empty() {
+            if !block.is_empty() {
+                let card = block
+                    .join("\n")
+                    .parse()
+                    .map_err(|e: ParseError| e.offset_lines(start).on_day(4))?;
+                cards.push(card);
+                block.clear();
+            }
+        } else {
+            if block.is_empty() {
+                start = idx;
+            }
+            block.push(line);
+        }
+    }
+
+    Ok(Bingo { draws, cards })
+}
+
+pub fn part1(bingo: &Bingo) -> u32 {
+    // To guarantee victory against the giant squid, figure out which board
+    // will win first. What will your final score be if you choose that board?
+    let wins = bingo.play(&Pattern::STANDARD);
+    wins.first().expect("Found no winning board.").score
+}
+
+pub fn part2(bingo: &Bingo) -> u32 {
+    // Figure out which board will win last. Once it wins, what would its final
+    // score be?
+    let wins = bingo.play(&Pattern::STANDARD);
+    wins.last().expect("Found no winning board.").score
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const EXAMPLE: &str = "\
+7,4,9,5,11,17,23,2,0,14,21,24,10,16,13,6,15,25,12,22,18,20,8,19,3,26,1
+
+22 13 17 11  0
+ 8  2 23  4 24
+21  9 14 16  7
+ 6 10  3 18  5
+ 1 12 20 15 19
+
+ 3 15  0  2 22
+ 9 18 13 17  5
+19  8  7 25 23
+20 11 10 24  4
+14 21 16 12  6
+
+14 21 17 24  4
+10 16 15  9 19
+18  8 23 26 20
+22 11 13  6  5
+ 2  0 12  3  7";
+
+    #[test]
+    fn part1_example() {
+        assert_eq!(part1(&parse(EXAMPLE).unwrap()), 4512);
+    }
+
+    #[test]
+    fn part2_example() {
+        assert_eq!(part2(&parse(EXAMPLE).unwrap()), 1924);
+    }
+
+    const CARD: &str = "\
+1 2 3 4 5
+6 7 8 9 10
+11 12 13 14 15
+16 17 18 19 20
+21 22 23 24 25";
+
+    #[test]
+    fn bingo_card_win() {
+        let mut card: BingoCard = CARD.parse().unwrap();
+        for draw in [1, 7, 13, 19] {
+            card.mark(draw);
+        }
+        // A diagonal does not count.
+        card.mark(25);
+        assert!(!card.win(&Pattern::STANDARD));
+        assert!(card.win(&[Pattern::Diagonals]));
+
+        // Neither do incomplete lines, but complete columns do.
+        for draw in [2, 3, 4, 12, 17] {
+            card.mark(draw);
+            assert!(!card.win(&Pattern::STANDARD));
+        }
+        assert!(card.mark(22));
+        assert!(card.win(&Pattern::STANDARD));
+        assert_eq!(
+            card.score(),
+            325 - 1 - 7 - 13 - 19 - 25 - 2 - 3 - 4 - 12 - 17 - 22
+        );
+        assert!(!card.mark(26));
+    }
+
+    #[test]
+    fn bingo_card_win_row() {
+        let mut card: BingoCard = CARD.parse().unwrap();
+        for draw in [6, 7, 8, 9] {
+            card.mark(draw);
+        }
+        assert!(!card.win(&Pattern::STANDARD));
+        card.mark(10);
+        assert!(card.win(&Pattern::STANDARD));
+    }
+
+    #[test]
+    fn patterns() {
+        let mut card: BingoCard = "1 2 3\n4 5 6".parse().unwrap();
+        for draw in [1, 3, 4] {
+            card.mark(draw);
+        }
+        assert!(!card.win(&[Pattern::Corners]));
+        card.mark(6);
+        assert!(card.win(&[Pattern::Corners]));
+        assert!(!card.win(&[Pattern::Full, Pattern::Diagonals]));
+
+        let mask = Pattern::mask("#.#\n.#.").unwrap();
+        assert_eq!(
+            mask,
+            Pattern::Mask(vec![
+                Position::new(0, 0),
+                Position::new(2, 0),
+                Position::new(1, 1)
+            ])
+        );
+        assert!(!card.win(std::slice::from_ref(&mask)));
+        card.mark(5);
+        assert!(card.win(&[mask]));
+        card.mark(2);
+        assert!(card.win(&[Pattern::Full]));
+    }
+
+    #[test]
+    fn game_log() {
+        let wins = parse(EXAMPLE).unwrap().play(&Pattern::STANDARD);
+        let order: Vec<(usize, u32)> = wins.iter().map(|w| (w.card, w.draw)).collect();
+        assert_eq!(order, [(2, 24), (0, 16), (1, 13)]);
+        assert_eq!(wins[0].turn, 11);
+
+        let bingo = parse("3,1,2,4\n\n1 2\n\n3 4\n5 6\n7 8").unwrap();
+        let wins = bingo.play(&[Pattern::Full]);
+        assert_eq!(
+            wins,
+            [Win {
+                card: 0,
+                turn: 2,
+                draw: 2,
+                score: 0,
+            }]
+        );
+    }
+
+    #[test]
+    fn invalid_card() {
+        let error =
+            parse("1,2\n\n1 2 3 4 5\n1 2 3 4 5\n1 2 x 4 5\n1 2 3 4 5\n1 2 3 4 5").unwrap_err();
+        assert_eq!((error.line(), error.column(), error.text()), (5, 5, "x"));
+        let error = parse("1,2\n\n1 2 3\n1 2\n\n1").unwrap_err();
+        assert_eq!(
+            (error.line(), error.message()),
+            (4, "Expected 3 numbers, found 2")
+        );
+    }
+}
@@ -0,0 +1,621 @@
+use crate::error::{parse_lines, parse_number};
+use crate::ParseError;
+use counter::Counter;
+use std::collections::{BTreeMap, HashMap};
+use std::str::FromStr;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Position {
+    x: i32,
+    y: i32,
+}
+
+impl Position {
+    pub fn new(x: i32, y: i32) -> Self {
+        Position { x, y }
+    }
+}
+
+/// How to turn a line into the points it covers.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Rasterisation {
+    /// Horizontal, vertical and 45-degree diagonal lines only, as in the
+    /// puzzle. Other lines are rejected.
+    #[default]
+    Strict,
+    /// A point per step along the longest axis, the nearest to the line.
+    Bresenham,
+    /// Only the points exactly on the line.
+    Lattice,
+}
+
+#[derive(Debug, Clone, Copy)]
+pub struct VentLine {
+    start_position: Position,
+    end_position: Position,
+}
+
+impl VentLine {
+    pub fn new(start_position: Position, end_position: Position) -> Self {
+        VentLine {
+            start_position,
+            end_position,
+        }
+    }
+
+    pub fn is_vertical(self) -> bool {
+        self.start_position.x == self.end_position.x
+    }
+
+    pub fn is_horizontal(self) -> bool {
+        self.start_position.y == self.end_position.y
+    }
+
+    /// Whether the line is at 45 degrees.
+    pub fn is_diagonal(self) -> bool {
+        let (dx, dy) = self.delta();
+        dx.abs() == dy.abs()
+    }
+
+    fn delta(self) -> (i64, i64) {
+        (
+            self.end_position.x as i64 - self.start_position.x as i64,
+            self.end_position.y as i64 - self.start_position.y as i64,
+        )
+    }
+
+    /// The points covered by the line, from start to end, or `None` for a
+    /// line the rasterisation does not accept.
+    pub fn points(self, rasterisation: Rasterisation) -> Option<impl Iterator<Item = Position>> {
+        let (dx, dy) = self.delta();
+        let conforming = self.is_horizontal() || self.is_vertical() || self.is_diagonal();
+        if rasterisation == Rasterisation::Strict && !conforming {
+            return None;
+        }
+        let lattice = rasterisation == Rasterisation::Lattice;
+
+        // Points are computed from the lowest end, so that a line covers the
+        // same points whichever way it goes.
+        let steps = dx.abs().max(dy.abs());
+        let reversed = self.end_position < self.start_position;
+        let (from, dx, dy) = if reversed {
+            (self.end_position, -dx, -dy)
+        } else {
+            (self.start_position, dx, dy)
+        };
+
+        // The products of steps and deltas overflow an `i64` at the extremes
+        // of the coordinates.
+        let (steps, dx, dy) = (steps as i128, dx as i128, dy as i128);
+        Some(
+            (0..=steps)
+                .map(move |i| if reversed { steps - i } else { i })
+                .filter(move |i| {
+                    !lattice || (i * dx % steps.max(1) == 0 && i * dy % steps.max(1) == 0)
+                })
+                .map(move |i| {
+                    Position::new(
+                        (from.x as i128 + round_ratio(i * dx, steps)) as i32,
+                        (from.y as i128 + round_ratio(i * dy, steps)) as i32,
+                    )
+                }),
+        )
+    }
+
+    /// The points covered by the line, from start to end, with the Bresenham
+    /// rasterisation (which is exact for the lines of the puzzle).
+    pub fn iter(self) -> impl Iterator<Item = Position> {
+        self.points(Rasterisation::Bresenham)
+            .expect("Bresenham rasterisation accepts all lines")
+    }
+}
+
+// `numerator / denominator` rounded to the nearest integer, halves up.
+fn round_ratio(numerator: i128, denominator: i128) -> i128 {
+    if denominator == 0 {
+        0
+    } else {
+        (2 * numerator + denominator).div_euclid(2 * denominator)
+    }
+}
+
+fn parse_position(line: &str, position: &str) -> Result<Position, ParseError> {
+    let (x, y) = position
+        .split_once(',')
+        .ok_or_else(|| ParseError::new("Invalid position", line, position))?;
+
+    Ok(Position::new(
+        parse_number(line, x)?,
+        parse_number(line, y)?,
+    ))
+}
+
+impl FromStr for VentLine {
+    type Err = ParseError;
+
+    fn from_str(vents: &str) -> Result<Self, Self::Err> {
+        let (start, end) = vents
+            .split_once(" -> ")
+            .ok_or_else(|| ParseError::missing("Missing end position", vents))?;
+
+        Ok(VentLine {
+            start_position: parse_position(vents, start)?,
+            end_position: parse_position(vents, end)?,
+        })
+    }
+}
+
+pub fn parse(input: &str) -> Result<Vec<VentLine>, ParseError> {
+    parse_lines(input, str::parse).map_err(|e| e.on_day(5))
+}
+
+/// How to count the points covered by the lines.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Method {
+    /// Count every point of the lines.
+    Counter(Rasterisation),
+    /// Sweep over the ends of the lines, and work out where they cross. Only
+    /// for the lines accepted by the strict rasterisation, but independent
+    /// of their lengths: for `n` lines crossing at `k` points, it takes
+    /// `O((n + k) log n)` time.
+    SweepLine,
+}
+
+/// The number of points covered by each number of lines.
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct Coverage(BTreeMap<usize, usize>);
+
+impl Coverage {
+    /// Returns `None` if the method does not accept one of the lines.
+    pub fn new(vent_lines: &[VentLine], method: Method) -> Option<Self> {
+        match method {
+            Method::Counter(rasterisation) => {
+                let mut counter = Counter::<Position>::new();
+                for line in vent_lines {
+                    counter.update(line.points(rasterisation)?);
+                }
+                Some(counter.values().copied().collect())
+            }
+            Method::SweepLine => sweep(vent_lines),
+        }
+    }
+
+    /// Number of points covered by exactly `depth` lines, for each depth.
+    pub fn histogram(&self) -> &BTreeMap<usize, usize> {
+        &self.0
+    }
+
+    /// Number of points covered by at least `depth` lines.
+    pub fn at_least(&self, depth: usize) -> usize {
+        self.0.range(depth.max(1)..).map(|(_, points)| points).sum()
+    }
+
+    /// Number of points where at least two lines overlap.
+    pub fn overlaps(&self) -> usize {
+        self.at_least(2)
+    }
+
+    fn add(&mut self, depth: usize, points: usize) {
+        *self.0.entry(depth).or_default() += points;
+    }
+
+    fn remove(&mut self, depth: usize, points: usize) {
+        if let Some(count) = self.0.get_mut(&depth) {
+            *count -= points;
+            if *count == 0 {
+                self.0.remove(&depth);
+            }
+        }
+    }
+}
+
+impl FromIterator<usize> for Coverage {
+    // From the depth of every covered point.
+    fn from_iter<I: IntoIterator<Item = usize>>(depths: I) -> Self {
+        let mut coverage = Coverage::default();
+        for depth in depths {
+            coverage.add(depth, 1);
+        }
+        coverage
+    }
+}
+
+// The directions of the lines accepted by the strict rasterisation. Each line
+// lies on `key = a * x + b * y`, and its points are told apart by `x` (or `y`
+// for vertical lines).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+enum Direction {
+    Horizontal,
+    Vertical,
+    Diagonal,
+    AntiDiagonal,
+}
+
+impl Direction {
+    fn of(line: VentLine) -> Option<Self> {
+        let (dx, dy) = line.delta();
+        match (dx, dy) {
+            (_, 0) => Some(Direction::Horizontal),
+            (0, _) => Some(Direction::Vertical),
+            _ if dx == dy => Some(Direction::Diagonal),
+            _ if dx == -dy => Some(Direction::AntiDiagonal),
+            _ => None,
+        }
+    }
+
+    fn coefficients(self) -> (i64, i64) {
+        match self {
+            Direction::Horizontal => (0, 1),
+            Direction::Vertical => (1, 0),
+            Direction::Diagonal => (1, -1),
+            Direction::AntiDiagonal => (1, 1),
+        }
+    }
+
+    fn key(self, (x, y): (i64, i64)) -> i64 {
+        let (a, b) = self.coefficients();
+        a * x + b * y
+    }
+
+    fn parameter(self, (x, y): (i64, i64)) -> i64 {
+        match self {
+            Direction::Vertical => y,
+            _ => x,
+        }
+    }
+
+    // The point of the line with the given key at the given parameter.
+    fn point(self, key: i64, parameter: i64) -> (i64, i64) {
+        match self {
+            Direction::Horizontal => (parameter, key),
+            Direction::Vertical => (key, parameter),
+            Direction::Diagonal => (parameter, parameter - key),
+            Direction::AntiDiagonal => (parameter, key - parameter),
+        }
+    }
+
+    // The point where the line with key `key` crosses the line of `other`
+    // with key `other_key`, if it has integer coordinates.
+    fn crossing(self, key: i64, other: Direction, other_key: i64) -> Option<(i64, i64)> {
+        let (a1, b1) = self.coefficients();
+        let (a2, b2) = other.coefficients();
+        let det = a1 * b2 - a2 * b1;
+        let x = key * b2 - other_key * b1;
+        let y = a1 * other_key - a2 * key;
+        if det == 0 || x % det != 0 || y % det != 0 {
+            return None;
+        }
+
+        Some((x / det, y / det))
+    }
+}
+
+// Consecutive points of a line covered by the same number of lines, from
+// parameter `from` to `to` included.
+#[derive(Debug, Clone, Copy)]
+struct Run {
+    direction: Direction,
+    key: i64,
+    from: i64,
+    to: i64,
+    depth: usize,
+}
+
+impl Run {
+    // The keys of the lines of `direction` going through the run, from the
+    // lowest to the highest.
+    fn span(&self, direction: Direction) -> (i64, i64) {
+        let from = direction.key(self.direction.point(self.key, self.from));
+        let to = direction.key(self.direction.point(self.key, self.to));
+        (from.min(to), from.max(to))
+    }
+}
+
+// The points where runs of the `first` direction cross runs of the `second`,
+// with the depths of both. The sweep goes over the keys of the `first`
+// direction: runs of the `second` are active over the keys they span, and
+// each run of the `first` looks up the active keys it spans.
+fn crossings(first: &[Run], second: &[Run]) -> Vec<((i64, i64), usize, usize)> {
+    // Runs of the `second` direction become active before the runs of the
+    // `first` at the same key look them up, and inactive after.
+    const START: u8 = 0;
+    const LOOKUP: u8 = 1;
+    const END: u8 = 2;
+
+    let (Some(run), Some(other)) = (first.first(), second.first()) else {
+        return Vec::new();
+    };
+    let (direction, other_direction) = (run.direction, other.direction);
+
+    let mut events = Vec::with_capacity(first.len() + 2 * second.len());
+    for (idx, run) in first.iter().enumerate() {
+        events.push((run.key, LOOKUP, idx));
+    }
+    for (idx, run) in second.iter().enumerate() {
+        let (from, to) = run.span(direction);
+        events.push((from, START, idx));
+        events.push((to, END, idx));
+    }
+    events.sort_unstable();
+
+    // The runs of the `second` direction by key. Runs on the same line do
+    // not overlap, so there is at most one active per key.
+    let mut active = BTreeMap::new();
+    let mut found = Vec::new();
+    for (key, kind, idx) in events {
+        match kind {
+            START => {
+                active.insert(second[idx].key, second[idx].depth);
+            }
+            END => {
+                active.remove(&second[idx].key);
+            }
+            _ => {
+                let run = &first[idx];
+                let (from, to) = run.span(other_direction);
+                for (&other_key, &depth) in active.range(from..=to) {
+                    if let Some(point) = direction.crossing(key, other_direction, other_key) {
+                        found.push((point, run.depth, depth));
+                    }
+                }
+            }
+        }
+    }
+
+    found
+}
+
+fn sweep(vent_lines: &[VentLine]) -> Option<Coverage> {
+    // The ends of the lines on each line of each direction, with +1 at the
+    // start of a line and -1 after its end.
+    let mut ends: HashMap<(Direction, i64), Vec<(i64, isize)>> = HashMap::new();
+    for &line in vent_lines {
+        let direction = Direction::of(line)?;
+        let start = (line.start_position.x as i64, line.start_position.y as i64);
+        let end = (line.end_position.x as i64, line.end_position.y as i64);
+        let (from, to) = (direction.parameter(start), direction.parameter(end));
+        let key_ends = ends.entry((direction, direction.key(start))).or_default();
+        key_ends.push((from.min(to), 1));
+        key_ends.push((from.max(to) + 1, -1));
+    }
+
+    let mut coverage = Coverage::default();
+    let mut runs: HashMap<Direction, Vec<Run>> = HashMap::new();
+    for ((direction, key), mut key_ends) in ends {
+        key_ends.sort_unstable();
+        let mut depth = 0;
+        for (idx, &(parameter, change)) in key_ends.iter().enumerate() {
+            depth += change;
+            match key_ends.get(idx + 1) {
+                Some(&(next, _)) if depth > 0 && next > parameter => {
+                    let run = Run {
+                        direction,
+                        key,
+                        from: parameter,
+                        to: next - 1,
+                        depth: depth as usize,
+                    };
+                    coverage.add(run.depth, (run.to - run.from + 1) as usize);
+                    runs.entry(direction).or_default().push(run);
+                }
+                _ => {}
+            }
+        }
+    }
+
+    // Points where lines of different directions cross are covered by the
+    // runs of each direction: count them once, with the sum of their depths.
+    let directions = [
+        Direction::Horizontal,
+        Direction::Vertical,
+        Direction::Diagonal,
+        Direction::AntiDiagonal,
+    ];
+    let runs_of = |direction| runs.get(&direction).map_or(&[][..], Vec::as_slice);
+    let mut points: HashMap<(i64, i64), HashMap<Direction, usize>> = HashMap::new();
+    for (idx, &first) in directions.iter().enumerate() {
+        for &second in &directions[idx + 1..] {
+            for (point, depth, other_depth) in crossings(runs_of(first), runs_of(second)) {
+                let depths = points.entry(point).or_default();
+                depths.insert(first, depth);
+                depths.insert(second, other_depth);
+            }
+        }
+    }
+    for depths in points.values() {
+        for &depth in depths.values() {
+            coverage.remove(depth, 1);
+        }
+        coverage.add(depths.values().sum(), 1);
+    }
+
+    Some(coverage)
+}
+
+pub fn part1(vent_lines: &[VentLine]) -> usize {
+    // Consider only horizontal and vertical lines. At how many points do at
+    // least two lines overlap?
+    let vent_lines: Vec<VentLine> = vent_lines
+        .iter()
+        .filter(|line| line.is_horizontal() || line.is_vertical())
+        .copied()
+        .collect();
+    Coverage::new(&vent_lines, Method::SweepLine)
+        .expect("Horizontal and vertical lines are accepted")
+        .overlaps()
+}
+
+pub fn part2(vent_lines: &[VentLine]) -> usize {
+    // Consider all of the lines. At how many points do at least two lines
+    // overlap?
+    Coverage::new(vent_lines, Method::SweepLine)
+        .or_else(|| Coverage::new(vent_lines, Method::Counter(Rasterisation::Bresenham)))
+        .expect("Bresenham rasterisation accepts all lines")
+        .overlaps()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const EXAMPLE: &str = "\
+0,9 -> 5,9
+8,0 -> 0,8
+9,4 -> 3,4
+2,2 -> 2,1
+7,0 -> 7,4
+6,4 -> 2,0
+0,9 -> 2,9
+3,4 -> 1,4
+0,0 -> 8,8
+5,5 -> 8,2";
+
+    #[test]
+    fn part1_example() {
+        assert_eq!(part1(&parse(EXAMPLE).unwrap()), 5);
+    }
+
+    #[test]
+    fn part2_example() {
+        assert_eq!(part2(&parse(EXAMPLE).unwrap()), 12);
+    }
+
+    fn positions(line: &str) -> Vec<(i32, i32)> {
+        let line: VentLine = line.parse().unwrap();
+        line.iter().map(|p| (p.x, p.y)).collect()
+    }
+
+    #[test]
+    fn vent_line_iter() {
+        assert_eq!(positions("1,1 -> 1,3"), [(1, 1), (1, 2), (1, 3)]);
+        assert_eq!(positions("9,7 -> 7,7"), [(9, 7), (8, 7), (7, 7)]);
+        assert_eq!(positions("1,1 -> 3,3"), [(1, 1), (2, 2), (3, 3)]);
+        assert_eq!(positions("9,7 -> 7,9"), [(9, 7), (8, 8), (7, 9)]);
+        assert_eq!(positions("4,4 -> 4,4"), [(4, 4)]);
+    }
+
+    fn rasterise(line: &str, rasterisation: Rasterisation) -> Option<Vec<(i32, i32)>> {
+        let line: VentLine = line.parse().unwrap();
+        let points = line.points(rasterisation)?;
+        Some(points.map(|p| (p.x, p.y)).collect())
+    }
+
+    #[test]
+    fn any_angle() {
+        assert_eq!(rasterise("0,0 -> 4,2", Rasterisation::Strict), None);
+        assert_eq!(
+            rasterise("9,7 -> 7,9", Rasterisation::Strict),
+            Some(positions("9,7 -> 7,9"))
+        );
+
+        assert_eq!(
+            positions("0,0 -> 4,2"),
+            [(0, 0), (1, 1), (2, 1), (3, 2), (4, 2)]
+        );
+        // The same points, in reverse.
+        assert_eq!(
+            positions("4,2 -> 0,0"),
+            [(4, 2), (3, 2), (2, 1), (1, 1), (0, 0)]
+        );
+        assert_eq!(
+            positions("0,0 -> 1,-3"),
+            [(0, 0), (0, -1), (1, -2), (1, -3)]
+        );
+
+        // The extremes of the coordinates.
+        let line: VentLine = "2147483647,2147483647 -> -2147483648,-2147483647"
+            .parse()
+            .unwrap();
+        let points: Vec<Position> = line.iter().take(2).collect();
+        assert_eq!(
+            points,
+            [
+                Position::new(2147483647, 2147483647),
+                Position::new(2147483646, 2147483646)
+            ]
+        );
+        let mut lattice = line.points(Rasterisation::Lattice).unwrap();
+        assert_eq!(lattice.next(), Some(Position::new(2147483647, 2147483647)));
+
+        assert_eq!(
+            rasterise("0,0 -> 4,2", Rasterisation::Lattice),
+            Some(vec![(0, 0), (2, 1), (4, 2)])
+        );
+        assert_eq!(
+            rasterise("6,1 -> 0,5", Rasterisation::Lattice),
+            Some(vec![(6, 1), (3, 3), (0, 5)])
+        );
+        assert_eq!(
+            rasterise("0,0 -> 3,1", Rasterisation::Lattice),
+            Some(vec![(0, 0), (3, 1)])
+        );
+        assert_eq!(
+            rasterise("4,4 -> 4,4", Rasterisation::Lattice),
+            Some(vec![(4, 4)])
+        );
+    }
+
+    // Lines in every direction, from a small linear congruential generator.
+    fn generated_lines(count: usize, size: u64) -> Vec<VentLine> {
+        let mut seed = 12345u64;
+        let mut next = move || {
+            seed = seed
+                .wrapping_mul(6364136223846793005)
+                .wrapping_add(1442695040888963407);
+            ((seed >> 33) % size) as i32
+        };
+
+        (0..count)
+            .map(|idx| {
+                let start = Position::new(next(), next());
+                let length = next();
+                let (dx, dy) = [(1, 0), (0, 1), (1, 1), (1, -1)][idx % 4];
+                let end = Position::new(start.x + dx * length, start.y + dy * length);
+                VentLine::new(start, end)
+            })
+            .collect()
+    }
+
+    #[test]
+    fn sweep_line() {
+        let lines = parse(EXAMPLE).unwrap();
+        let coverage = Coverage::new(&lines, Method::SweepLine).unwrap();
+        assert_eq!(
+            coverage,
+            Coverage::new(&lines, Method::Counter(Rasterisation::Strict)).unwrap()
+        );
+        assert_eq!(
+            coverage.histogram(),
+            &BTreeMap::from([(1, 27), (2, 10), (3, 2)])
+        );
+        assert_eq!(coverage.at_least(3), 2);
+
+        for (count, size) in [(200, 40), (2000, 300)] {
+            let lines = generated_lines(count, size);
+            assert_eq!(
+                Coverage::new(&lines, Method::SweepLine),
+                Coverage::new(&lines, Method::Counter(Rasterisation::Strict))
+            );
+        }
+
+        let line: VentLine = "0,0 -> 4,2".parse().unwrap();
+        assert_eq!(Coverage::new(&[line], Method::SweepLine), None);
+    }
+
+    #[test]
+    fn sweep_line_long_lines() {
+        // Two overlapping horizontal lines, crossed by a diagonal outside of
+        // the overlap and by a vertical line inside it.
+        let lines = parse(
+            "0,0 -> 4000000,0\n1000000,0 -> 5000000,0\n0,1 -> 3000000,-2999999\n2000000,2 -> 2000000,-2",
+        )
+        .unwrap();
+        let coverage = Coverage::new(&lines, Method::SweepLine).unwrap();
+        assert_eq!(coverage.overlaps(), 3000002);
+        assert_eq!(coverage.at_least(3), 1);
+        assert_eq!(
+            coverage.histogram(),
+            &BTreeMap::from([(1, 5000003), (2, 3000001), (3, 1)])
+        );
+    }
+}
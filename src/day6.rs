@@ -0,0 +1,437 @@
+use crate::error::parse_number;
+use crate::ParseError;
+use num_bigint::BigUint;
+use std::str::FromStr;
+
+/// How a species reproduces: each fish gives birth every `cycle` days, and
+/// newborns take `delay` more days before their first cycle starts.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Species {
+    cycle: usize,
+    delay: usize,
+}
+
+impl Species {
+    pub const LANTERNFISH: Species = Species { cycle: 7, delay: 2 };
+
+    /// Returns `None` for a cycle of 0 days.
+    pub fn new(cycle: usize, delay: usize) -> Option<Self> {
+        if cycle == 0 {
+            None
+        } else {
+            Some(Species { cycle, delay })
+        }
+    }
+
+    pub fn cycle(&self) -> usize {
+        self.cycle
+    }
+
+    pub fn delay(&self) -> usize {
+        self.delay
+    }
+
+    /// The number of different timers, the highest being that of newborns.
+    pub fn timers(&self) -> usize {
+        self.cycle + self.delay
+    }
+
+    /// A population from the comma-separated timers of the fishes.
+    pub fn population(self, input: &str) -> Result<LanternFishPopulation, ParseError> {
+        let line = input.trim();
+        let mut population = vec![0; self.timers()];
+        for fish in line.split(',') {
+            let timer: usize = parse_number(line, fish)?;
+            let count = population
+                .get_mut(timer)
+                .ok_or_else(|| ParseError::new("Invalid timer", line, fish))?;
+            *count += 1;
+        }
+
+        Ok(LanternFishPopulation {
+            species: self,
+            counts: population,
+        })
+    }
+
+    // How many fishes of each timer there are after a day, from those of the
+    // day before: `next[i]` is the sum of `matrix[i][j] * previous[j]`.
+    fn transition<A: Arithmetic>(&self, arithmetic: &A) -> Option<Matrix<A::Value>> {
+        let size = self.timers();
+        let mut matrix = Matrix::zero(size, arithmetic);
+        for timer in 1..size {
+            matrix.0[timer - 1][timer] = arithmetic.one();
+        }
+        // Fishes at timer 0 give birth and restart their cycle, at the same
+        // timer as their newborns when there is no delay.
+        matrix.0[size - 1][0] = arithmetic.one();
+        let restart = &mut matrix.0[self.cycle - 1][0];
+        *restart = arithmetic.add(restart, &arithmetic.one())?;
+
+        Some(matrix)
+    }
+}
+
+impl Default for Species {
+    fn default() -> Self {
+        Species::LANTERNFISH
+    }
+}
+
+/// The numbers a projection is computed with.
+pub trait Arithmetic {
+    type Value: Clone;
+
+    fn zero(&self) -> Self::Value;
+    fn one(&self) -> Self::Value;
+    /// Returns `None` if the number cannot be represented.
+    fn count(&self, count: u64) -> Option<Self::Value>;
+    /// Returns `None` on overflow.
+    fn add(&self, a: &Self::Value, b: &Self::Value) -> Option<Self::Value>;
+    /// Returns `None` on overflow.
+    fn mul(&self, a: &Self::Value, b: &Self::Value) -> Option<Self::Value>;
+}
+
+/// `u64`, failing on overflow.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Checked;
+
+impl Arithmetic for Checked {
+    type Value = u64;
+
+    fn zero(&self) -> u64 {
+        0
+    }
+
+    fn one(&self) -> u64 {
+        1
+    }
+
+    fn count(&self, count: u64) -> Option<u64> {
+        Some(count)
+    }
+
+    fn add(&self, a: &u64, b: &u64) -> Option<u64> {
+        a.checked_add(*b)
+    }
+
+    fn mul(&self, a: &u64, b: &u64) -> Option<u64> {
+        a.checked_mul(*b)
+    }
+}
+
+/// Remainders of the division by the modulus.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Modulo(u64);
+
+impl Modulo {
+    /// Returns `None` for a modulus of 0.
+    pub fn new(modulus: u64) -> Option<Self> {
+        if modulus == 0 {
+            None
+        } else {
+            Some(Modulo(modulus))
+        }
+    }
+
+    pub fn modulus(&self) -> u64 {
+        self.0
+    }
+}
+
+impl Arithmetic for Modulo {
+    type Value = u64;
+
+    fn zero(&self) -> u64 {
+        0
+    }
+
+    fn one(&self) -> u64 {
+        1 % self.0
+    }
+
+    fn count(&self, count: u64) -> Option<u64> {
+        count.checked_rem(self.0)
+    }
+
+    fn add(&self, a: &u64, b: &u64) -> Option<u64> {
+        Some(((*a as u128 + *b as u128) % self.0 as u128) as u64)
+    }
+
+    fn mul(&self, a: &u64, b: &u64) -> Option<u64> {
+        Some(((*a as u128 * *b as u128) % self.0 as u128) as u64)
+    }
+}
+
+/// Arbitrary precision, never overflowing.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Exact;
+
+impl Arithmetic for Exact {
+    type Value = BigUint;
+
+    fn zero(&self) -> BigUint {
+        BigUint::ZERO
+    }
+
+    fn one(&self) -> BigUint {
+        BigUint::from(1u8)
+    }
+
+    fn count(&self, count: u64) -> Option<BigUint> {
+        Some(BigUint::from(count))
+    }
+
+    fn add(&self, a: &BigUint, b: &BigUint) -> Option<BigUint> {
+        Some(a + b)
+    }
+
+    fn mul(&self, a: &BigUint, b: &BigUint) -> Option<BigUint> {
+        Some(a * b)
+    }
+}
+
+// A square matrix, row by row.
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct Matrix<T>(Vec<Vec<T>>);
+
+impl<T: Clone> Matrix<T> {
+    fn zero<A: Arithmetic<Value = T>>(size: usize, arithmetic: &A) -> Self {
+        Matrix(vec![vec![arithmetic.zero(); size]; size])
+    }
+
+    fn identity<A: Arithmetic<Value = T>>(size: usize, arithmetic: &A) -> Self {
+        let mut matrix = Matrix::zero(size, arithmetic);
+        for (idx, row) in matrix.0.iter_mut().enumerate() {
+            row[idx] = arithmetic.one();
+        }
+        matrix
+    }
+
+    fn mul<A: Arithmetic<Value = T>>(&self, other: &Self, arithmetic: &A) -> Option<Self> {
+        let size = self.0.len();
+        let mut product = Matrix::zero(size, arithmetic);
+        for (i, row) in self.0.iter().enumerate() {
+            for j in 0..size {
+                let mut sum = arithmetic.zero();
+                for (k, value) in row.iter().enumerate() {
+                    sum = arithmetic.add(&sum, &arithmetic.mul(value, &other.0[k][j])?)?;
+                }
+                product.0[i][j] = sum;
+            }
+        }
+
+        Some(product)
+    }
+
+    // By squaring, with `log2(exponent)` multiplications.
+    fn pow<A: Arithmetic<Value = T>>(&self, mut exponent: u64, arithmetic: &A) -> Option<Self> {
+        let mut result = Matrix::identity(self.0.len(), arithmetic);
+        let mut square = self.clone();
+        while exponent > 0 {
+            if exponent & 1 == 1 {
+                result = result.mul(&square, arithmetic)?;
+            }
+            exponent >>= 1;
+            if exponent > 0 {
+                square = square.mul(&square, arithmetic)?;
+            }
+        }
+
+        Some(result)
+    }
+}
+
+/// The number of fishes for each timer.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LanternFishPopulation {
+    species: Species,
+    counts: Vec<u64>,
+}
+
+impl LanternFishPopulation {
+    pub fn species(&self) -> Species {
+        self.species
+    }
+
+    pub fn counts(&self) -> &[u64] {
+        &self.counts
+    }
+
+    pub fn total_population(&self) -> u64 {
+        self.counts.iter().sum()
+    }
+
+    /// The number of fishes for each timer after `days` days, or `None` on
+    /// overflow. The projection goes through the number of descendants of a
+    /// single fish, so it overflows as soon as those do, which can happen a
+    /// few days before the population itself overflows.
+    pub fn project<A: Arithmetic>(&self, days: u64, arithmetic: &A) -> Option<Vec<A::Value>> {
+        if self.total_population() == 0 {
+            return Some(vec![arithmetic.zero(); self.counts.len()]);
+        }
+
+        let matrix = self.species.transition(arithmetic)?.pow(days, arithmetic)?;
+        let counts = self
+            .counts
+            .iter()
+            .map(|&count| arithmetic.count(count))
+            .collect::<Option<Vec<_>>>()?;
+
+        matrix
+            .0
+            .iter()
+            .map(|row| {
+                row.iter()
+                    .zip(&counts)
+                    .try_fold(arithmetic.zero(), |sum, (a, b)| {
+                        arithmetic.add(&sum, &arithmetic.mul(a, b)?)
+                    })
+            })
+            .collect()
+    }
+
+    /// The total population after `days` days, or `None` on overflow.
+    pub fn project_total<A: Arithmetic>(&self, days: u64, arithmetic: &A) -> Option<A::Value> {
+        self.project(days, arithmetic)?
+            .iter()
+            .try_fold(arithmetic.zero(), |sum, count| arithmetic.add(&sum, count))
+    }
+}
+
+impl Default for LanternFishPopulation {
+    fn default() -> Self {
+        let species = Species::default();
+        LanternFishPopulation {
+            species,
+            counts: vec![0; species.timers()],
+        }
+    }
+}
+
+impl Iterator for LanternFishPopulation {
+    type Item = LanternFishPopulation;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        // Give birth to new lanternfish (highest timer). Don't forget to add
+        // the original fishes at the end of their cycle.
+        self.counts.rotate_left(1);
+        self.counts[self.species.cycle - 1] += self.counts[self.species.timers() - 1];
+        Some(self.clone())
+    }
+}
+
+impl FromStr for LanternFishPopulation {
+    type Err = ParseError;
+
+    fn from_str(input: &str) -> Result<Self, Self::Err> {
+        Species::LANTERNFISH.population(input)
+    }
+}
+
+pub fn parse(input: &str) -> Result<LanternFishPopulation, ParseError> {
+    input.parse().map_err(|e: ParseError| e.on_day(6))
+}
+
+pub fn part1(population: &LanternFishPopulation) -> u64 {
+    // Find a way to simulate lanternfish. How many lanternfish would there be
+    // after 80 days?
+    population
+        .project_total(80, &Checked)
+        .expect("Overflow after 80 days.")
+}
+
+pub fn part2(population: &LanternFishPopulation) -> u64 {
+    // How many lanternfish would there be after 256 days?
+    population
+        .project_total(256, &Checked)
+        .expect("Overflow after 256 days.")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const EXAMPLE: &str = "\
+3,4,3,1,2";
+
+    #[test]
+    fn part1_example() {
+        assert_eq!(part1(&parse(EXAMPLE).unwrap()), 5934);
+    }
+
+    #[test]
+    fn part2_example() {
+        assert_eq!(part2(&parse(EXAMPLE).unwrap()), 26984457539);
+    }
+
+    #[test]
+    fn population_after_18_days() {
+        let mut population = parse(EXAMPLE).unwrap();
+        assert_eq!(population.nth(17).unwrap().total_population(), 26);
+    }
+
+    #[test]
+    fn projection() {
+        let population = parse(EXAMPLE).unwrap();
+        let mut simulated = population.clone();
+        assert_eq!(
+            population.project(18, &Checked),
+            Some(simulated.nth(17).unwrap().counts().to_vec())
+        );
+        assert_eq!(population.project_total(0, &Checked), Some(5));
+
+        // Overflows a `u64`, but not the other arithmetics.
+        assert_eq!(population.project_total(10_000, &Checked), None);
+        let exact = population.project_total(10_000, &Exact).unwrap();
+        assert_eq!(exact.bits(), 1260);
+        let modulo = population.project_total(10_000, &Modulo::new(1_000_000_007).unwrap());
+        assert_eq!(modulo.map(BigUint::from), Some(exact % 1_000_000_007u64));
+    }
+
+    #[test]
+    fn other_species() {
+        // Reproducing every 3 days, newborns starting after 1 more day.
+        let species = Species::new(3, 1).unwrap();
+        let mut population = species.population("0").unwrap();
+        let totals: Vec<u64> = (1..=8)
+            .map(|_| population.next().unwrap().total_population())
+            .collect();
+        assert_eq!(totals, [2, 2, 2, 3, 4, 4, 5, 7]);
+
+        let population = species.population("0").unwrap();
+        assert_eq!(population.project_total(8, &Checked), Some(7));
+        assert!(species.population("4").is_err());
+        assert_eq!(Species::new(0, 2), None);
+    }
+
+    #[test]
+    fn no_delay() {
+        let species = Species::new(3, 0).unwrap();
+        let mut population = species.population("0").unwrap();
+        let simulated: Vec<u64> = (1..=6)
+            .map(|_| population.next().unwrap().total_population())
+            .collect();
+        assert_eq!(simulated, [2, 2, 2, 4, 4, 4]);
+
+        let population = species.population("0").unwrap();
+        let projected: Vec<u64> = (1..=6)
+            .map(|days| population.project_total(days, &Checked).unwrap())
+            .collect();
+        assert_eq!(projected, simulated);
+    }
+
+    #[test]
+    fn projection_limits() {
+        assert_eq!(Modulo::new(0), None);
+        assert_eq!(Modulo::new(7).map(|m| m.modulus()), Some(7));
+
+        // Nothing to overflow without fishes.
+        let population = LanternFishPopulation::default();
+        assert_eq!(population.project_total(1000, &Checked), Some(0));
+        assert_eq!(
+            population.project(1000, &Exact),
+            Some(vec![BigUint::ZERO; 9])
+        );
+    }
+}
@@ -0,0 +1,238 @@
+use crate::error::parse_number;
+use crate::ParseError;
+
+pub fn parse(input: &str) -> Result<Vec<i64>, ParseError> {
+    let line = input.trim();
+    line.split(',')
+        .map(|position| parse_number(line, position))
+        .collect::<Result<Vec<i64>, _>>()
+        .map_err(|e| e.on_day(7))
+}
+
+/// The fuel a crab needs to move, which must grow faster and faster (or at
+/// least as fast) with the distance. Closures can be used as costs.
+pub trait FuelCost {
+    fn fuel(&self, distance: u64) -> u64;
+
+    /// Targets among which one is optimal, if they can be found without a
+    /// search. `positions` are sorted.
+    fn candidates(&self, _positions: &[i64]) -> Option<Vec<i64>> {
+        None
+    }
+}
+
+impl<F: Fn(u64) -> u64> FuelCost for F {
+    fn fuel(&self, distance: u64) -> u64 {
+        self(distance)
+    }
+}
+
+/// One unit of fuel per step, as in part 1.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Linear;
+
+impl FuelCost for Linear {
+    fn fuel(&self, distance: u64) -> u64 {
+        distance
+    }
+
+    // The (lower) median.
+    fn candidates(&self, positions: &[i64]) -> Option<Vec<i64>> {
+        Some(vec![positions[(positions.len() - 1) / 2]])
+    }
+}
+
+/// One more unit of fuel for each step, as in part 2.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Triangular;
+
+impl FuelCost for Triangular {
+    fn fuel(&self, distance: u64) -> u64 {
+        // The sum of the integers from 1 to n is given by: n * (n + 1) / 2.
+        distance * (distance + 1) / 2
+    }
+
+    // An optimum is within 1/2 of the mean.
+    fn candidates(&self, positions: &[i64]) -> Option<Vec<i64>> {
+        Some(around_mean(positions))
+    }
+}
+
+/// The square of the distance.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Quadratic;
+
+impl FuelCost for Quadratic {
+    fn fuel(&self, distance: u64) -> u64 {
+        distance * distance
+    }
+
+    // The mean is the optimum.
+    fn candidates(&self, positions: &[i64]) -> Option<Vec<i64>> {
+        Some(around_mean(positions))
+    }
+}
+
+// The integers just below and above the mean.
+fn around_mean(positions: &[i64]) -> Vec<i64> {
+    let sum: i128 = positions.iter().map(|&p| p as i128).sum();
+    let count = positions.len() as i128;
+    let floor = sum.div_euclid(count) as i64;
+    vec![floor, floor + 1]
+}
+
+/// Where the crabs align, with the fuel spent by each of them.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Alignment {
+    pub position: i64,
+    pub fuel: u64,
+    pub crabs: Vec<u64>,
+}
+
+impl Alignment {
+    pub fn new(positions: &[i64], target: i64, cost: &impl FuelCost) -> Self {
+        let crabs: Vec<u64> = positions
+            .iter()
+            .map(|p| cost.fuel(p.abs_diff(target)))
+            .collect();
+
+        Alignment {
+            position: target,
+            fuel: crabs.iter().sum(),
+            crabs,
+        }
+    }
+}
+
+fn total_fuel(positions: &[i64], target: i64, cost: &impl FuelCost) -> u64 {
+    positions
+        .iter()
+        .map(|p| cost.fuel(p.abs_diff(target)))
+        .sum()
+}
+
+/// The alignment spending the least fuel, to the lowest position if several
+/// do, or `None` without crabs.
+pub fn align(positions: &[i64], cost: &impl FuelCost) -> Option<Alignment> {
+    let mut sorted = positions.to_vec();
+    sorted.sort_unstable();
+    let (&min, &max) = (sorted.first()?, sorted.last()?);
+
+    let target = match cost.candidates(&sorted) {
+        Some(candidates) => candidates
+            .into_iter()
+            .map(|target| target.clamp(min, max))
+            .min_by_key(|&target| (total_fuel(&sorted, target, cost), target))?,
+        None => {
+            // The total fuel is convex: search for the first target from
+            // which it stops decreasing.
+            let (mut low, mut high) = (min, max);
+            while low < high {
+                let mid = low + (high - low) / 2;
+                if total_fuel(&sorted, mid, cost) <= total_fuel(&sorted, mid + 1, cost) {
+                    high = mid;
+                } else {
+                    low = mid + 1;
+                }
+            }
+            low
+        }
+    };
+
+    Some(Alignment::new(positions, target, cost))
+}
+
+pub fn part1(positions: &[i64]) -> u64 {
+    // Determine the horizontal position that the crabs can align to using the
+    // least fuel possible. How much fuel must they spend to align to that
+    // position?
+    align(positions, &Linear).expect("No crabs.").fuel
+}
+
+pub fn part2(positions: &[i64]) -> u64 {
+    // Determine the horizontal position that the crabs can align to using the
+    // least fuel possible so they can make you an escape route! How much fuel
+    // must they spend to align to that position?
+    align(positions, &Triangular).expect("No crabs.").fuel
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const EXAMPLE: &str = "\
+16,1,2,0,4,2,7,1,2,14";
+
+    #[test]
+    fn part1_example() {
+        assert_eq!(part1(&parse(EXAMPLE).unwrap()), 37);
+    }
+
+    #[test]
+    fn part2_example() {
+        assert_eq!(part2(&parse(EXAMPLE).unwrap()), 168);
+    }
+
+    #[test]
+    fn alignment() {
+        let positions = parse(EXAMPLE).unwrap();
+        let alignment = align(&positions, &Linear).unwrap();
+        assert_eq!((alignment.position, alignment.fuel), (2, 37));
+        assert_eq!(alignment.crabs, [14, 1, 0, 2, 2, 0, 5, 1, 0, 12]);
+
+        let alignment = align(&positions, &Triangular).unwrap();
+        assert_eq!((alignment.position, alignment.fuel), (5, 168));
+        assert_eq!(alignment.crabs[0], 66);
+        assert_eq!(align(&[], &Linear), None);
+    }
+
+    // The lowest of the best alignments, trying every target.
+    fn brute_force(positions: &[i64], cost: &impl FuelCost) -> (i64, u64) {
+        let min = *positions.iter().min().unwrap();
+        let max = *positions.iter().max().unwrap();
+        (min..=max)
+            .map(|target| (target, total_fuel(positions, target, cost)))
+            .min_by_key(|&(target, fuel)| (fuel, target))
+            .unwrap()
+    }
+
+    #[test]
+    fn optimal_alignments() {
+        let mut seed = 7u64;
+        let positions: Vec<i64> = (0..101)
+            .map(|_| {
+                seed = seed.wrapping_mul(6364136223846793005).wrapping_add(1);
+                (seed >> 40) as i64 % 5000 - 1000
+            })
+            .collect();
+
+        let best = |cost: &dyn Fn(&[i64]) -> Alignment| {
+            let alignment = cost(&positions);
+            assert_eq!(alignment.crabs.iter().sum::<u64>(), alignment.fuel);
+            (alignment.position, alignment.fuel)
+        };
+        assert_eq!(
+            best(&|p| align(p, &Linear).unwrap()),
+            brute_force(&positions, &Linear)
+        );
+        assert_eq!(
+            best(&|p| align(p, &Triangular).unwrap()),
+            brute_force(&positions, &Triangular)
+        );
+        assert_eq!(
+            best(&|p| align(p, &Quadratic).unwrap()),
+            brute_force(&positions, &Quadratic)
+        );
+        let cubic = |d: u64| d * d * d;
+        assert_eq!(
+            best(&|p| align(p, &cubic).unwrap()),
+            brute_force(&positions, &cubic)
+        );
+        // Plateaus do not get in the way of the search.
+        let free_ride = |d: u64| d.saturating_sub(1000);
+        assert_eq!(
+            best(&|p| align(p, &free_ride).unwrap()),
+            brute_force(&positions, &free_ride)
+        );
+    }
+}
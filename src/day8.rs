@@ -0,0 +1,459 @@
+use crate::error::{char_at, parse_lines};
+use crate::ParseError;
+use std::collections::BTreeMap;
+use std::fmt;
+use std::str::FromStr;
+
+const DECIMAL: &str = "abcefg cf acdeg acdfg bcdf abdfg abdefg acf abcdefg abcdfg";
+const HEXADECIMAL: &str = "abcdef bdefg abeg cdefg abdeg abde";
+
+// The segments of a pattern, one bit per letter from `a` to `z`.
+type Segments = u32;
+
+const LETTERS: usize = 26;
+
+// Patterns using the first `letters` letters.
+fn parse_patterns(line: &str, text: &str, letters: usize) -> Result<Vec<Segments>, ParseError> {
+    let patterns = text
+        .split_whitespace()
+        .map(|p| {
+            let mut segments = 0;
+            for (idx, c) in p.char_indices() {
+                let letter = (c as u32).wrapping_sub('a' as u32) as usize;
+                let segment = match c {
+                    'a'..='z' if letter < letters => 1 << letter,
+                    'a'..='z' => {
+                        return Err(ParseError::new("Unknown wire", line, char_at(p, idx)))
+                    }
+                    _ => return Err(ParseError::new("Invalid segment", line, char_at(p, idx))),
+                };
+                if segments & segment != 0 {
+                    return Err(ParseError::new("Repeated segment", line, char_at(p, idx)));
+                }
+                segments |= segment;
+            }
+            Ok(segments)
+        })
+        .collect::<Result<Vec<Segments>, _>>()?;
+
+    if patterns.is_empty() {
+        Err(ParseError::missing("Missing patterns", line))
+    } else {
+        Ok(patterns)
+    }
+}
+
+fn segment_letters(segments: Segments) -> impl Iterator<Item = char> {
+    ('a'..='z').filter(move |c| segments & (1 << (*c as u32 - 'a' as u32)) != 0)
+}
+
+/// The symbols shown by a display, as the segments they light up. The value
+/// of a symbol is its index.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DisplayAlphabet {
+    segments: usize,
+    symbols: Vec<Segments>,
+}
+
+impl DisplayAlphabet {
+    /// The digits from 0 to 9 on a seven-segment display, as in the puzzle.
+    pub fn decimal() -> Self {
+        DECIMAL.parse().expect("Invalid decimal alphabet")
+    }
+
+    /// The digits from 0 to F on a seven-segment display.
+    pub fn hexadecimal() -> Self {
+        format!("{} {}", DECIMAL, HEXADECIMAL)
+            .parse()
+            .expect("Invalid hexadecimal alphabet")
+    }
+
+    /// The number of segments, up to the last one used by the symbols.
+    pub fn segments(&self) -> usize {
+        self.segments
+    }
+
+    pub fn len(&self) -> usize {
+        self.symbols.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.symbols.is_empty()
+    }
+
+    /// Read an entry of this display, whose wires can only be those of its
+    /// segments.
+    pub fn entry(&self, line: &str) -> Result<Day8Entry, ParseError> {
+        parse_entry(line, self.segments)
+    }
+}
+
+impl FromStr for DisplayAlphabet {
+    type Err = ParseError;
+
+    // The symbols' segments, separated by whitespace.
+    fn from_str(input: &str) -> Result<Self, Self::Err> {
+        let symbols = parse_patterns(input, input, LETTERS)?;
+        for (idx, symbol) in symbols.iter().enumerate() {
+            if symbols[..idx].contains(symbol) {
+                let text = input.split_whitespace().nth(idx).unwrap_or_default();
+                return Err(ParseError::new("Repeated symbol", input, text));
+            }
+        }
+
+        let all = symbols.iter().fold(0, |all, s| all | s);
+        Ok(DisplayAlphabet {
+            segments: (Segments::BITS - all.leading_zeros()) as usize,
+            symbols,
+        })
+    }
+}
+
+/// Why the wiring of an entry could not be found.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum DecodeError {
+    /// A wire that the display does not have.
+    UnknownWire(char),
+    /// No wiring shows the patterns as symbols of the display.
+    Inconsistent,
+    /// Several wirings do.
+    Ambiguous,
+    /// The output values, or their sum, do not fit in 64 bits.
+    Overflow,
+}
+
+impl fmt::Display for DecodeError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            DecodeError::UnknownWire(wire) => write!(f, "Unknown wire: {}", wire),
+            DecodeError::Inconsistent => write!(f, "No wiring matches the patterns"),
+            DecodeError::Ambiguous => write!(f, "Several wirings match the patterns"),
+            DecodeError::Overflow => write!(f, "Overflow on the output values"),
+        }
+    }
+}
+
+impl std::error::Error for DecodeError {}
+
+/// The wiring of an entry, and its output.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Decoding {
+    /// The segment each wire is connected to.
+    pub mapping: BTreeMap<char, char>,
+    /// The values of the output symbols.
+    pub digits: Vec<usize>,
+    base: usize,
+}
+
+impl Decoding {
+    /// The output read as a number, in the base of the number of symbols, or
+    /// `None` on overflow.
+    pub fn value(&self) -> Option<u64> {
+        self.digits.iter().try_fold(0u64, |acc, &d| {
+            acc.checked_mul(self.base as u64)?.checked_add(d as u64)
+        })
+    }
+}
+
+// Wirings found by a search, from wires to segments.
+struct Wiring<'a> {
+    // For each pattern seen, the symbols it can be.
+    candidates: &'a [(Segments, Vec<Segments>)],
+    segments: &'a [Option<usize>],
+}
+
+impl Wiring<'_> {
+    fn image(&self, wires: Segments) -> Segments {
+        segment_letters(wires)
+            .filter_map(|c| self.segments[(c as u32 - 'a' as u32) as usize])
+            .fold(0, |image, s| image | 1 << s)
+    }
+
+    // Whether each pattern can still be shown as one of its symbols: the
+    // wires already connected must light up some of its segments, and the
+    // others none of them.
+    fn possible(&self, connected: Segments) -> bool {
+        self.candidates.iter().all(|(pattern, symbols)| {
+            let inside = self.image(pattern & connected);
+            let outside = self.image(connected & !pattern);
+            symbols
+                .iter()
+                .any(|symbol| symbol & inside == inside && symbol & outside == 0)
+        })
+    }
+}
+
+// Connect the wires from `wire` on, counting the wirings found up to 2.
+fn search(
+    candidates: &[(Segments, Vec<Segments>)],
+    mapping: &mut Vec<Option<usize>>,
+    wire: usize,
+    used: Segments,
+    found: &mut Vec<Vec<Option<usize>>>,
+) {
+    if found.len() > 1 {
+        return;
+    }
+    if wire == mapping.len() {
+        found.push(mapping.clone());
+        return;
+    }
+
+    for segment in (0..mapping.len()).filter(|s| used & (1 << s) == 0) {
+        mapping[wire] = Some(segment);
+        let wiring = Wiring {
+            candidates,
+            segments: mapping,
+        };
+        if wiring.possible((1 << (wire + 1)) - 1) {
+            search(candidates, mapping, wire + 1, used | 1 << segment, found);
+        }
+    }
+    mapping[wire] = None;
+}
+
+#[derive(Debug, Clone)]
+pub struct Day8Entry {
+    patterns: Vec<Segments>,
+    output: Vec<Segments>,
+}
+
+impl Day8Entry {
+    /// Find the wiring of a seven-segment display of decimal digits.
+    pub fn decode(&self) -> Result<Decoding, DecodeError> {
+        self.decode_with(&DisplayAlphabet::decimal())
+    }
+
+    /// Find the only wiring showing all the patterns and the output as
+    /// symbols of the display.
+    pub fn decode_with(&self, display: &DisplayAlphabet) -> Result<Decoding, DecodeError> {
+        let mut seen: Vec<Segments> = self.patterns.iter().chain(&self.output).copied().collect();
+        seen.sort_unstable();
+        seen.dedup();
+
+        let wires = seen.iter().fold(0, |all, s| all | s);
+        if let Some(wire) = segment_letters(wires >> display.segments << display.segments).next() {
+            return Err(DecodeError::UnknownWire(wire));
+        }
+
+        let candidates: Vec<(Segments, Vec<Segments>)> = seen
+            .iter()
+            .map(|&pattern| {
+                let symbols = display
+                    .symbols
+                    .iter()
+                    .copied()
+                    .filter(|s| s.count_ones() == pattern.count_ones())
+                    .collect();
+                (pattern, symbols)
+            })
+            .collect();
+
+        let mut found = Vec::new();
+        search(
+            &candidates,
+            &mut vec![None; display.segments],
+            0,
+            0,
+            &mut found,
+        );
+        let mapping = match found.as_slice() {
+            [] => return Err(DecodeError::Inconsistent),
+            [mapping] => mapping,
+            _ => return Err(DecodeError::Ambiguous),
+        };
+
+        let wiring = Wiring {
+            candidates: &candidates,
+            segments: mapping,
+        };
+        let digits = self
+            .output
+            .iter()
+            .map(|&o| {
+                let symbol = wiring.image(o);
+                display.symbols.iter().position(|&s| s == symbol)
+            })
+            .collect::<Option<Vec<usize>>>()
+            .ok_or(DecodeError::Inconsistent)?;
+
+        let letter = |idx: usize| char::from(b'a' + idx as u8);
+        Ok(Decoding {
+            mapping: mapping
+                .iter()
+                .enumerate()
+                .filter_map(|(wire, segment)| Some((letter(wire), letter((*segment)?))))
+                .collect(),
+            digits,
+            base: display.len(),
+        })
+    }
+}
+
+fn parse_entry(line: &str, letters: usize) -> Result<Day8Entry, ParseError> {
+    let (left, right) = line
+        .split_once('|')
+        .ok_or_else(|| ParseError::missing("Missing output values", line))?;
+
+    Ok(Day8Entry {
+        patterns: parse_patterns(line, left, letters)?,
+        output: parse_patterns(line, right, letters)?,
+    })
+}
+
+impl FromStr for Day8Entry {
+    type Err = ParseError;
+
+    // Wires from `a` to `z`, for any display.
+    fn from_str(input: &str) -> Result<Self, Self::Err> {
+        parse_entry(input, LETTERS)
+    }
+}
+
+pub fn parse(input: &str) -> Result<Vec<Day8Entry>, ParseError> {
+    let display = DisplayAlphabet::decimal();
+    parse_lines(input, |line| display.entry(line)).map_err(|e| e.on_day(8))
+}
+
+pub fn part1(entries: &[Day8Entry]) -> usize {
+    // In the output values, how many times do digits 1, 4, 7, or 8 appear?
+    // 1 uses 2 segments, 4 uses 4, 7 uses 3 and 8 uses all 7.
+    const UNIQUE_LENGTHS: [u32; 4] = [2, 4, 3, 7];
+    entries
+        .iter()
+        .flat_map(|entry| entry.output.iter())
+        .filter(|o| UNIQUE_LENGTHS.contains(&o.count_ones()))
+        .count()
+}
+
+pub fn part2(entries: &[Day8Entry]) -> Result<u64, DecodeError> {
+    // For each entry, determine all of the wire/segment connections and decode
+    // the four-digit output values. What do you get if you add up all of the
+    // output values?
+    entries.iter().try_fold(0u64, |sum, e| {
+        let value = e.decode()?.value().ok_or(DecodeError::Overflow)?;
+        sum.checked_add(value).ok_or(DecodeError::Overflow)
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const EXAMPLE: &str = "\
+be cfbegad cbdgef fgaecd cgeb fdcge agebfd fecdb fabcd edb | fdgacbe cefdb cefbgd gcbe
+edbfga begcd cbg gc gcadebf fbgde acbgfd abcde gfcbed gfec | fcgedb cgb dgebacf gc
+fgaebd cg bdaec gdafb agbcfd gdcbef bgcad gfac gcb cdgabef | cg cg fdcagb cbg
+fbegcd cbd adcefb dageb afcb bc aefdc ecdab fgdeca fcdbega | efabcd cedba gadfec cb
+aecbfdg fbg gf bafeg dbefa fcge gcbea fcaegb dgceab fcbdga | gecf egdcabf bgf bfgea
+fgeab ca afcebg bdacfeg cfaedg gcfdb baec bfadeg bafgc acf | gebdcfa ecba ca fadegcb
+dbcfg fgd bdegcaf fgec aegbdf ecdfab fbedc dacgb gdcebf gf | cefg dcbef fcge gbcadfe
+bdfegc cbegaf gecbf dfcage bdacg ed bedf ced adcbefg gebcd | ed bcgafe cdgba cbgef
+egadfb cdbfeg cegd fecab cgb gbdefca cg fgcdab egfdb bfceg | gbdfcae bgc cg cgb
+gcafb gcf dcaebfg ecagb gf abcdeg gaef cafbge fdbac fegbdc | fgae cfgab fg bagce";
+
+    #[test]
+    fn part1_example() {
+        assert_eq!(part1(&parse(EXAMPLE).unwrap()), 26);
+    }
+
+    #[test]
+    fn part2_example() {
+        assert_eq!(part2(&parse(EXAMPLE).unwrap()), Ok(61229));
+    }
+
+    #[test]
+    fn undecodable_entries() {
+        assert_eq!(
+            part2(&parse("ab ab | ab").unwrap()),
+            Err(DecodeError::Ambiguous)
+        );
+    }
+
+    #[test]
+    fn decode() {
+        let entry: Day8Entry =
+            "acedgfb cdfbe gcdfa fbcad dab cefabd cdfgeb eafb cagedb ab | cdfeb fcadb cdfeb cdbaf"
+                .parse()
+                .unwrap();
+        let decoding = entry.decode().unwrap();
+        assert_eq!(decoding.value(), Some(5353));
+        assert_eq!(decoding.digits, [5, 3, 5, 3]);
+        let mapping: String = decoding.mapping.values().collect();
+        assert_eq!(mapping, "cfgabde");
+    }
+
+    // Scramble the wires of some symbols of a display with `wiring`, which
+    // gives the wire of each segment.
+    fn scramble(display: &str, symbols: &[usize], wiring: &str) -> String {
+        let all: Vec<&str> = display.split_whitespace().collect();
+        let wiring: Vec<char> = wiring.chars().collect();
+        symbols
+            .iter()
+            .map(|&idx| {
+                all[idx]
+                    .chars()
+                    .map(|c| wiring[(c as u8 - b'a') as usize])
+                    .collect::<String>()
+            })
+            .collect::<Vec<_>>()
+            .join(" ")
+    }
+
+    #[test]
+    fn hexadecimal() {
+        let display = format!("{} {}", DECIMAL, HEXADECIMAL);
+        let patterns = scramble(&display, &(0..16).collect::<Vec<_>>(), "gafbdce");
+        let output = scramble(&display, &[15, 0, 0, 13], "gafbdce");
+        let entry: Day8Entry = format!("{} | {}", patterns, output).parse().unwrap();
+
+        let decoding = entry.decode_with(&DisplayAlphabet::hexadecimal()).unwrap();
+        assert_eq!(decoding.value(), Some(0xF00D));
+        let mapping: String = decoding.mapping.keys().collect();
+        assert_eq!(mapping, "abcdefg");
+        let mapping: String = decoding.mapping.values().collect();
+        assert_eq!(mapping, "bdfegca");
+    }
+
+    #[test]
+    fn fourteen_segments() {
+        // Symbols of a display with more segments, like fourteen-segment ones.
+        let display = "abcefgh abcdjlm adef aefgh aefg adefh bcefgh jm bcde efikl def \
+                       bcefik bcefjl abcdef abcdjm gi bh";
+        let wiring = "mlkjihgfedcba";
+        let patterns = scramble(display, &(0..17).collect::<Vec<_>>(), wiring);
+        let output = scramble(display, &[7, 0, 2], wiring);
+        let entry: Day8Entry = format!("{} | {}", patterns, output).parse().unwrap();
+
+        let decoding = entry.decode_with(&display.parse().unwrap()).unwrap();
+        assert_eq!(decoding.digits, [7, 0, 2]);
+        assert_eq!(decoding.mapping[&'a'], 'm');
+    }
+
+    #[test]
+    fn decode_errors() {
+        let entry: Day8Entry = "abc abd | abc".parse().unwrap();
+        assert_eq!(entry.decode(), Err(DecodeError::Inconsistent));
+        let entry: Day8Entry = "ab | abh".parse().unwrap();
+        assert_eq!(entry.decode(), Err(DecodeError::UnknownWire('h')));
+
+        let display: DisplayAlphabet = "a b ab".parse().unwrap();
+        let entry: Day8Entry = "a b ab | a".parse().unwrap();
+        assert_eq!(entry.decode_with(&display), Err(DecodeError::Ambiguous));
+        assert!("a ab a".parse::<DisplayAlphabet>().is_err());
+    }
+
+    #[test]
+    fn unknown_wires() {
+        // The puzzle's display only has wires from `a` to `g`.
+        let error = parse("ab gf | abc\nab | abh").unwrap_err();
+        assert_eq!(
+            (error.line(), error.column(), error.text(), error.message()),
+            (2, 8, "h", "Unknown wire")
+        );
+        let display: DisplayAlphabet = "a b ab".parse().unwrap();
+        let error = display.entry("a b | c").unwrap_err();
+        assert_eq!((error.column(), error.message()), (7, "Unknown wire"));
+        assert!(display.entry("a b | ab").is_ok());
+    }
+}
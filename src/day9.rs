@@ -0,0 +1,315 @@
+use crate::grid::{Grid, Position};
+use crate::ParseError;
+use std::cmp::Reverse;
+use std::str::FromStr;
+
+/// A basin: cells lower than the walls, connected to one another.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Basin {
+    /// In reading order.
+    pub cells: Vec<Position>,
+    /// The cells at the bottom of the basin, from which the height only
+    /// rises, in reading order.
+    pub low_points: Vec<Position>,
+    /// The cells next to a wall, in reading order.
+    pub boundary: Vec<Position>,
+    /// The lowest wall next to the basin, where it would overflow first.
+    /// `None` without walls.
+    pub spill_point: Option<Position>,
+    /// From the bottom of the basin to its spill point.
+    pub depth: Option<u32>,
+    /// The index of the basin on the other side of the spill point, the
+    /// lowest if there are several.
+    pub drains_into: Option<usize>,
+}
+
+impl Basin {
+    pub fn size(&self) -> usize {
+        self.cells.len()
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct HeightMap(Grid<u32>);
+
+impl HeightMap {
+    pub fn get(&self, p: Position) -> Option<&u32> {
+        self.0.get(p)
+    }
+
+    /// The cells lower than all their neighbours, with their heights.
+    pub fn low_points(&self) -> impl Iterator<Item = (Position, u32)> + '_ {
+        self.0.enumerate().filter_map(move |(p, h)| {
+            if self.0.neighbours4(p).all(|adjacent| self.0[adjacent] > *h) {
+                Some((p, *h))
+            } else {
+                None
+            }
+        })
+    }
+
+    // The cells from which the height only rises: those of flat areas
+    // without lower neighbours.
+    fn minima(&self) -> Grid<bool> {
+        let grid = &self.0;
+        let mut minima = Grid::new(grid.width(), grid.height(), false);
+        let mut seen = Grid::new(grid.width(), grid.height(), false);
+
+        for p in grid.positions() {
+            if seen[p] {
+                continue;
+            }
+
+            let height = grid[p];
+            let mut flat = vec![p];
+            let mut lowest = true;
+            seen[p] = true;
+            let mut idx = 0;
+            while let Some(&q) = flat.get(idx) {
+                for adjacent in grid.neighbours4(q) {
+                    if grid[adjacent] < height {
+                        lowest = false;
+                    } else if grid[adjacent] == height && !seen[adjacent] {
+                        seen[adjacent] = true;
+                        flat.push(adjacent);
+                    }
+                }
+                idx += 1;
+            }
+
+            if lowest {
+                for q in flat {
+                    minima[q] = true;
+                }
+            }
+        }
+
+        minima
+    }
+
+    /// The basins separated by cells at least as high as `wall`, in the
+    /// reading order of their first cells.
+    pub fn watershed(&self, wall: u32) -> Vec<Basin> {
+        let grid = &self.0;
+        let mut labels: Grid<Option<usize>> = Grid::new(grid.width(), grid.height(), None);
+        let mut cells = Vec::new();
+        for p in grid.positions() {
+            if grid[p] >= wall || labels[p].is_some() {
+                continue;
+            }
+
+            let label = cells.len();
+            let mut basin = vec![p];
+            labels[p] = Some(label);
+            let mut idx = 0;
+            while let Some(&q) = basin.get(idx) {
+                for adjacent in grid.neighbours4(q) {
+                    if grid[adjacent] < wall && labels[adjacent].is_none() {
+                        labels[adjacent] = Some(label);
+                        basin.push(adjacent);
+                    }
+                }
+                idx += 1;
+            }
+
+            basin.sort_unstable_by_key(|p| (p.y, p.x));
+            cells.push(basin);
+        }
+
+        let minima = self.minima();
+        let is_wall = |p: Position| labels[p].is_none();
+        let lowest: Vec<u32> = cells
+            .iter()
+            .map(|basin| basin.iter().map(|&p| grid[p]).min().unwrap_or_default())
+            .collect();
+
+        cells
+            .into_iter()
+            .enumerate()
+            .map(|(label, basin)| {
+                let mut walls: Vec<Position> = basin
+                    .iter()
+                    .flat_map(|&p| grid.neighbours4(p))
+                    .filter(|&p| is_wall(p))
+                    .collect();
+                walls.sort_unstable_by_key(|p| (p.y, p.x));
+                walls.dedup();
+                let spill_point = walls.iter().copied().min_by_key(|&p| grid[p]);
+                let drains_into = spill_point.and_then(|spill| {
+                    grid.neighbours4(spill)
+                        .filter_map(|q| labels[q])
+                        .filter(|&other| other != label)
+                        .min_by_key(|&other| (lowest[other], other))
+                });
+
+                let low_points = basin.iter().copied().filter(|&p| minima[p]).collect();
+                let boundary = basin
+                    .iter()
+                    .copied()
+                    .filter(|&p| grid.neighbours4(p).any(is_wall))
+                    .collect();
+
+                Basin {
+                    cells: basin,
+                    low_points,
+                    boundary,
+                    spill_point,
+                    depth: spill_point.map(|spill| grid[spill] - lowest[label]),
+                    drains_into,
+                }
+            })
+            .collect()
+    }
+}
+
+impl FromStr for HeightMap {
+    type Err = ParseError;
+
+    fn from_str(map: &str) -> Result<Self, Self::Err> {
+        let grid = Grid::parse(map, |c| c.to_digit(10))?;
+        if grid.is_empty() {
+            return Err(ParseError::missing("Missing heights", map));
+        }
+
+        Ok(HeightMap(grid))
+    }
+}
+
+pub fn parse(input: &str) -> Result<HeightMap, ParseError> {
+    let height_map: HeightMap = input.parse().map_err(|e: ParseError| e.on_day(9))?;
+
+    // The second part needs the three largest basins.
+    let basins = height_map.watershed(9).len();
+    if basins < 3 {
+        let message = format!("Expected at least 3 basins, found {}", basins);
+        let last = input.lines().count().saturating_sub(1);
+        let line = input.lines().last().unwrap_or_default();
+        return Err(ParseError::missing(message, line)
+            .offset_lines(last)
+            .on_day(9));
+    }
+
+    Ok(height_map)
+}
+
+pub fn part1(height_map: &HeightMap) -> u32 {
+    // Find all of the low points on your heightmap. What is the sum of the
+    // risk levels of all low points on your heightmap?
+    height_map.low_points().map(|(_, h)| h + 1).sum()
+}
+
+pub fn part2(height_map: &HeightMap) -> usize {
+    // What do you get if you multiply together the sizes of the three largest
+    // basins?
+    let mut sizes: Vec<usize> = height_map.watershed(9).iter().map(Basin::size).collect();
+    sizes.sort_by_key(|&size| Reverse(size));
+    sizes[0..3].iter().product()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const EXAMPLE: &str = "\
+2199943210
+3987894921
+9856789892
+8767896789
+9899965678";
+
+    #[test]
+    fn part1_example() {
+        assert_eq!(part1(&parse(EXAMPLE).unwrap()), 15);
+    }
+
+    #[test]
+    fn part2_example() {
+        assert_eq!(part2(&parse(EXAMPLE).unwrap()), 1134);
+    }
+
+    #[test]
+    fn low_points() {
+        let low_points: Vec<(Position, u32)> = parse(EXAMPLE).unwrap().low_points().collect();
+        assert_eq!(
+            low_points,
+            [
+                (Position::new(1, 0), 1),
+                (Position::new(9, 0), 0),
+                (Position::new(2, 2), 5),
+                (Position::new(6, 4), 5),
+            ]
+        );
+    }
+
+    #[test]
+    fn too_few_basins() {
+        let error = parse("191\n999").unwrap_err();
+        assert_eq!(
+            (error.line(), error.column(), error.message()),
+            (2, 4, "Expected at least 3 basins, found 2")
+        );
+        assert_eq!(parse("").unwrap_err().message(), "Missing heights");
+    }
+
+    #[test]
+    fn watershed() {
+        let basins = parse(EXAMPLE).unwrap().watershed(9);
+        let sizes: Vec<usize> = basins.iter().map(Basin::size).collect();
+        assert_eq!(sizes, [3, 9, 14, 9]);
+        assert_eq!(basins[0].low_points, [Position::new(1, 0)]);
+        assert_eq!(
+            basins[0].boundary,
+            [Position::new(1, 0), Position::new(0, 1)]
+        );
+        assert_eq!(basins[0].spill_point, Some(Position::new(2, 0)));
+        assert_eq!(basins[0].depth, Some(8));
+        assert_eq!(basins[0].drains_into, Some(2));
+
+        // Two basins with two minima each, draining into each other, unless
+        // the wall is higher.
+        let map: HeightMap = "12634\n21540".parse().unwrap();
+        let basins = map.watershed(5);
+        assert_eq!(basins.len(), 2);
+        assert_eq!(
+            basins[0].low_points,
+            [Position::new(0, 0), Position::new(1, 1)]
+        );
+        assert_eq!(
+            basins[0].boundary,
+            [Position::new(1, 0), Position::new(1, 1)]
+        );
+        assert_eq!(
+            (
+                basins[0].spill_point,
+                basins[0].depth,
+                basins[0].drains_into
+            ),
+            (Some(Position::new(2, 1)), Some(4), Some(1))
+        );
+        assert_eq!(
+            basins[1].low_points,
+            [Position::new(3, 0), Position::new(4, 1)]
+        );
+        assert_eq!((basins[1].depth, basins[1].drains_into), (Some(5), Some(0)));
+
+        let basins = map.watershed(6);
+        assert_eq!(basins.len(), 1);
+        assert_eq!(basins[0].size(), 9);
+        assert_eq!(
+            (
+                basins[0].spill_point,
+                basins[0].depth,
+                basins[0].drains_into
+            ),
+            (Some(Position::new(2, 0)), Some(6), None)
+        );
+        assert_eq!(map.watershed(7)[0].spill_point, None);
+
+        // Flat bottoms are low points too.
+        let basins = "2251".parse::<HeightMap>().unwrap().watershed(5);
+        assert_eq!(
+            basins[0].low_points,
+            [Position::new(0, 0), Position::new(1, 0)]
+        );
+    }
+}
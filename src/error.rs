@@ -0,0 +1,165 @@
+use std::fmt;
+use std::str::FromStr;
+
+/// Error returned when parsing a puzzle input, locating the offending text.
+///
+/// Lines and columns start at 1, columns counting characters.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParseError {
+    day: u8,
+    line: usize,
+    column: usize,
+    text: String,
+    message: String,
+}
+
+impl ParseError {
+    // `text` is expected to be a slice of `line`, which is used to find its
+    // column. Otherwise, the error points at the start of the line.
+    pub(crate) fn new(message: impl Into<String>, line: &str, text: &str) -> Self {
+        let start = line.as_ptr() as usize;
+        let offset = (text.as_ptr() as usize).wrapping_sub(start);
+        let column = match line.get(..offset) {
+            Some(before) if offset + text.len() <= line.len() => before.chars().count() + 1,
+            _ => 1,
+        };
+
+        ParseError {
+            day: 0,
+            line: 1,
+            column,
+            text: text.to_string(),
+            message: message.into(),
+        }
+    }
+
+    // Point at the end of `line`, for something missing.
+    pub(crate) fn missing(message: impl Into<String>, line: &str) -> Self {
+        ParseError::new(message, line, &line[line.len()..])
+    }
+
+    // Account for the `lines` lines preceding the text that was parsed.
+    pub(crate) fn offset_lines(mut self, lines: usize) -> Self {
+        self.line += lines;
+        self
+    }
+
+    // Account for the `columns` characters preceding the text that was parsed.
+    pub(crate) fn offset_columns(mut self, columns: usize) -> Self {
+        self.column += columns;
+        self
+    }
+
+    pub(crate) fn on_day(mut self, day: u8) -> Self {
+        self.day = day;
+        self
+    }
+
+    pub fn day(&self) -> u8 {
+        self.day
+    }
+
+    pub fn line(&self) -> usize {
+        self.line
+    }
+
+    pub fn column(&self) -> usize {
+        self.column
+    }
+
+    pub fn text(&self) -> &str {
+        &self.text
+    }
+
+    pub fn message(&self) -> &str {
+        &self.message
+    }
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "Day {}, line {}, column {}: {}",
+            self.day, self.line, self.column, self.message
+        )?;
+        if !self.text.is_empty() {
+            write!(f, ": {:?}", self.text)?;
+        }
+
+        Ok(())
+    }
+}
+
+impl std::error::Error for ParseError {}
+
+// The slice of `line` holding the character starting at byte `idx`.
+pub(crate) fn char_at(line: &str, idx: usize) -> &str {
+    let len = line[idx..].chars().next().map_or(0, char::len_utf8);
+    &line[idx..idx + len]
+}
+
+// Parse `text`, a slice of `line`, as a number.
+pub(crate) fn parse_number<T: FromStr>(line: &str, text: &str) -> Result<T, ParseError> {
+    text.parse()
+        .map_err(|_| ParseError::new("Invalid number", line, text))
+}
+
+// Parse each line of `input` with `parser`, keeping track of the line numbers.
+pub(crate) fn parse_lines<T, F>(input: &str, parser: F) -> Result<Vec<T>, ParseError>
+where
+    F: Fn(&str) -> Result<T, ParseError>,
+{
+    input
+        .lines()
+        .enumerate()
+        .map(|(idx, line)| parser(line).map_err(|e| e.offset_lines(idx)))
+        .collect()
+}
+
+// Split `input` at its first blank line, into the text before and after it,
+// with the number of lines before the latter.
+pub(crate) fn split_blank_line(input: &str) -> Option<(&str, &str, usize)> {
+    let (idx, blank) = input
+        .lines()
+        .enumerate()
+        .find(|(_, line)| line.is_empty())?;
+    let start = blank.as_ptr() as usize - input.as_ptr() as usize;
+    let after = input[start..]
+        .split_once('\n')
+        .map_or("", |(_, after)| after);
+
+    Some((&input[..start], after, idx + 1))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn column() {
+        let line = "fold along z=7";
+        let error = ParseError::new("Invalid fold axis", line, &line[11..12]);
+        assert_eq!((error.line(), error.column(), error.text()), (1, 12, "z"));
+        assert_eq!(ParseError::missing("Missing", line).column(), 15);
+        // Not a slice of the line.
+        assert_eq!(ParseError::new("Invalid", line, "z").column(), 1);
+    }
+
+    #[test]
+    fn parse_lines_offsets() {
+        let error = parse_lines::<u8, _>("1\n2\nx", |line| parse_number(line, line)).unwrap_err();
+        assert_eq!(
+            error.on_day(1).to_string(),
+            "Day 1, line 3, column 1: Invalid number: \"x\""
+        );
+    }
+
+    #[test]
+    fn blank_lines() {
+        assert_eq!(split_blank_line("a\nb\n\nc\n"), Some(("a\nb\n", "c\n", 3)));
+        assert_eq!(split_blank_line("a\r\n\r\nb"), Some(("a\r\n", "b", 2)));
+        assert_eq!(split_blank_line("\na"), Some(("", "a", 1)));
+        assert_eq!(split_blank_line("a\n"), None);
+    }
+}
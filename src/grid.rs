@@ -0,0 +1,379 @@
+use crate::error::{char_at, parse_lines};
+use crate::ParseError;
+use std::fmt;
+use std::ops::{Index, IndexMut};
+
+// Offsets of the neighbours of a cell, in reading order.
+const ORTHOGONAL: [(isize, isize); 4] = [(0, -1), (-1, 0), (1, 0), (0, 1)];
+const ALL_AROUND: [(isize, isize); 8] = [
+    (-1, -1),
+    (0, -1),
+    (1, -1),
+    (-1, 0),
+    (1, 0),
+    (-1, 1),
+    (0, 1),
+    (1, 1),
+];
+
+/// Position of a cell: `x` is the column and `y` the row, starting from the
+/// top left corner.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Position {
+    pub x: usize,
+    pub y: usize,
+}
+
+impl Position {
+    pub fn new(x: usize, y: usize) -> Self {
+        Position { x, y }
+    }
+}
+
+/// What lies beyond the edges of a grid.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Edges {
+    /// Nothing: cells on the edges have fewer neighbours.
+    #[default]
+    Bounded,
+    /// The opposite edge, as on a torus.
+    Wrapping,
+}
+
+/// A rectangular grid of cells, stored row by row.
+#[derive(Debug, Default, Clone, PartialEq, Eq, Hash)]
+pub struct Grid<T> {
+    cells: Vec<T>,
+    width: usize,
+    height: usize,
+    edges: Edges,
+}
+
+impl<T> Grid<T> {
+    pub fn new(width: usize, height: usize, value: T) -> Self
+    where
+        T: Clone,
+    {
+        Grid {
+            cells: vec![value; width * height],
+            width,
+            height,
+            edges: Edges::Bounded,
+        }
+    }
+
+    pub fn from_fn(width: usize, height: usize, mut f: impl FnMut(Position) -> T) -> Self {
+        let cells = (0..height)
+            .flat_map(|y| (0..width).map(move |x| Position::new(x, y)))
+            .map(&mut f)
+            .collect();
+
+        Grid {
+            cells,
+            width,
+            height,
+            edges: Edges::Bounded,
+        }
+    }
+
+    /// Returns `None` if the rows do not all have the same length.
+    pub fn from_rows(rows: Vec<Vec<T>>) -> Option<Self> {
+        let height = rows.len();
+        let width = rows.first().map_or(0, Vec::len);
+        if rows.iter().any(|row| row.len() != width) {
+            return None;
+        }
+
+        Some(Grid {
+            cells: rows.into_iter().flatten().collect(),
+            width,
+            height,
+            edges: Edges::Bounded,
+        })
+    }
+
+    /// Parse a grid with a cell per character, ignoring trailing whitespace
+    /// on each line. `cell` returns `None` for invalid characters.
+    pub fn parse(input: &str, cell: impl Fn(char) -> Option<T>) -> Result<Self, ParseError> {
+        let rows = parse_lines(input, |line| {
+            let line = line.trim_end();
+            line.char_indices()
+                .map(|(idx, c)| {
+                    cell(c).ok_or_else(|| {
+                        ParseError::new("Invalid character", line, char_at(line, idx))
+                    })
+                })
+                .collect::<Result<Vec<T>, _>>()
+        })?;
+
+        let width = rows.first().map_or(0, Vec::len);
+        match rows.iter().position(|row| row.len() != width) {
+            Some(idy) => {
+                let line = input.lines().nth(idy).unwrap_or_default().trim_end();
+                let message = format!("Expected {} cells, found {}", width, rows[idy].len());
+                Err(ParseError::new(message, line, line).offset_lines(idy))
+            }
+            None => Ok(Grid {
+                height: rows.len(),
+                cells: rows.into_iter().flatten().collect(),
+                width,
+                edges: Edges::Bounded,
+            }),
+        }
+    }
+
+    pub fn with_edges(mut self, edges: Edges) -> Self {
+        self.edges = edges;
+        self
+    }
+
+    pub fn edges(&self) -> Edges {
+        self.edges
+    }
+
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    pub fn len(&self) -> usize {
+        self.cells.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.cells.is_empty()
+    }
+
+    pub fn contains(&self, p: Position) -> bool {
+        p.x < self.width && p.y < self.height
+    }
+
+    pub fn get(&self, p: Position) -> Option<&T> {
+        if self.contains(p) {
+            self.cells.get(p.y * self.width + p.x)
+        } else {
+            None
+        }
+    }
+
+    pub fn get_mut(&mut self, p: Position) -> Option<&mut T> {
+        if self.contains(p) {
+            self.cells.get_mut(p.y * self.width + p.x)
+        } else {
+            None
+        }
+    }
+
+    /// The cells, in reading order.
+    pub fn iter(&self) -> impl Iterator<Item = &T> {
+        self.cells.iter()
+    }
+
+    pub fn iter_mut(&mut self) -> impl Iterator<Item = &mut T> {
+        self.cells.iter_mut()
+    }
+
+    /// The positions of the cells, in reading order.
+    pub fn positions(&self) -> impl Iterator<Item = Position> {
+        let width = self.width;
+        (0..self.len()).map(move |idx| Position::new(idx % width, idx / width))
+    }
+
+    /// The cells along with their positions, in reading order.
+    pub fn enumerate(&self) -> impl Iterator<Item = (Position, &T)> {
+        self.positions().zip(self.cells.iter())
+    }
+
+    pub fn row(&self, y: usize) -> &[T] {
+        &self.cells[y * self.width..(y + 1) * self.width]
+    }
+
+    pub fn rows(&self) -> impl Iterator<Item = &[T]> {
+        // `chunks` panics on a chunk size of 0.
+        self.cells.chunks(self.width.max(1))
+    }
+
+    pub fn column(&self, x: usize) -> impl Iterator<Item = &T> {
+        self.cells.iter().skip(x).step_by(self.width.max(1))
+    }
+
+    pub fn columns(&self) -> impl Iterator<Item = impl Iterator<Item = &T>> {
+        (0..self.width).map(move |x| self.column(x))
+    }
+
+    /// The position at the given offset from `p`, if it is in the grid (or
+    /// always, when wrapping around the edges).
+    pub fn neighbour(&self, p: Position, dx: isize, dy: isize) -> Option<Position> {
+        match self.edges {
+            Edges::Bounded => {
+                let x = p.x.checked_add_signed(dx)?;
+                let y = p.y.checked_add_signed(dy)?;
+                Some(Position::new(x, y)).filter(|&p| self.contains(p))
+            }
+            Edges::Wrapping if self.is_empty() => None,
+            Edges::Wrapping => {
+                let x = (p.x as isize + dx).rem_euclid(self.width as isize);
+                let y = (p.y as isize + dy).rem_euclid(self.height as isize);
+                Some(Position::new(x as usize, y as usize))
+            }
+        }
+    }
+
+    /// The positions up, left, right and down of `p`.
+    pub fn neighbours4(&self, p: Position) -> impl Iterator<Item = Position> + '_ {
+        ORTHOGONAL
+            .iter()
+            .filter_map(move |&(dx, dy)| self.neighbour(p, dx, dy))
+    }
+
+    /// The positions all around `p`, diagonals included.
+    pub fn neighbours8(&self, p: Position) -> impl Iterator<Item = Position> + '_ {
+        ALL_AROUND
+            .iter()
+            .filter_map(move |&(dx, dy)| self.neighbour(p, dx, dy))
+    }
+
+    pub fn map<U>(&self, f: impl FnMut(&T) -> U) -> Grid<U> {
+        Grid {
+            cells: self.cells.iter().map(f).collect(),
+            width: self.width,
+            height: self.height,
+            edges: self.edges,
+        }
+    }
+
+    pub fn map_with_position<U>(&self, mut f: impl FnMut(Position, &T) -> U) -> Grid<U> {
+        Grid {
+            cells: self.enumerate().map(|(p, v)| f(p, v)).collect(),
+            width: self.width,
+            height: self.height,
+            edges: self.edges,
+        }
+    }
+}
+
+impl<T> Index<Position> for Grid<T> {
+    type Output = T;
+
+    fn index(&self, p: Position) -> &Self::Output {
+        self.get(p)
+            .unwrap_or_else(|| panic!("Position out of the grid: {:?}", p))
+    }
+}
+
+impl<T> IndexMut<Position> for Grid<T> {
+    fn index_mut(&mut self, p: Position) -> &mut Self::Output {
+        self.get_mut(p)
+            .unwrap_or_else(|| panic!("Position out of the grid: {:?}", p))
+    }
+}
+
+impl<T: fmt::Display> fmt::Display for Grid<T> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        for row in self.rows().filter(|row| !row.is_empty()) {
+            for cell in row {
+                write!(f, "{}", cell)?;
+            }
+            writeln!(f)?;
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn digits(input: &str) -> Grid<u32> {
+        Grid::parse(input, |c| c.to_digit(10)).unwrap()
+    }
+
+    #[test]
+    fn parse() {
+        let grid = digits("123\n456\n");
+        assert_eq!((grid.width(), grid.height()), (3, 2));
+        assert_eq!(grid[Position::new(2, 1)], 6);
+        assert_eq!(grid.get(Position::new(3, 0)), None);
+
+        let error = Grid::parse("123\n4x6", |c| c.to_digit(10)).unwrap_err();
+        assert_eq!((error.line(), error.column(), error.text()), (2, 2, "x"));
+        let error = Grid::parse("123\n45", |c| c.to_digit(10)).unwrap_err();
+        assert_eq!(
+            (error.line(), error.message()),
+            (2, "Expected 3 cells, found 2")
+        );
+    }
+
+    #[test]
+    fn rows_and_columns() {
+        let grid = digits("123\n456");
+        assert_eq!(grid.row(1), [4, 5, 6]);
+        assert_eq!(grid.rows().collect::<Vec<_>>(), [[1, 2, 3], [4, 5, 6]]);
+        assert_eq!(grid.column(1).collect::<Vec<_>>(), [&2, &5]);
+        assert_eq!(grid.columns().count(), 3);
+    }
+
+    #[test]
+    fn bounded_neighbours() {
+        let grid = digits("123\n456\n789");
+        let values = |ps: Vec<Position>| ps.into_iter().map(|p| grid[p]).collect::<Vec<_>>();
+
+        assert_eq!(
+            values(grid.neighbours4(Position::new(1, 1)).collect()),
+            [2, 4, 6, 8]
+        );
+        assert_eq!(
+            values(grid.neighbours4(Position::new(0, 0)).collect()),
+            [2, 4]
+        );
+        assert_eq!(
+            values(grid.neighbours8(Position::new(1, 1)).collect()),
+            [1, 2, 3, 4, 6, 7, 8, 9]
+        );
+        assert_eq!(
+            values(grid.neighbours8(Position::new(2, 2)).collect()),
+            [5, 6, 8]
+        );
+    }
+
+    #[test]
+    fn wrapping_neighbours() {
+        let grid = digits("123\n456\n789").with_edges(Edges::Wrapping);
+        let values = |ps: Vec<Position>| ps.into_iter().map(|p| grid[p]).collect::<Vec<_>>();
+
+        assert_eq!(
+            values(grid.neighbours4(Position::new(0, 0)).collect()),
+            [7, 3, 2, 4]
+        );
+        assert_eq!(
+            values(grid.neighbours8(Position::new(2, 2)).collect()),
+            [5, 6, 4, 8, 7, 2, 3, 1]
+        );
+        assert_eq!(
+            grid.neighbour(Position::new(2, 0), 4, -4),
+            Some(Position::new(0, 2))
+        );
+    }
+
+    #[test]
+    fn display() {
+        let grid = Grid::parse("#.\n.#", |c| Some(c == '#')).unwrap();
+        let rendered = grid.map(|&b| if b { '#' } else { '.' }).to_string();
+        assert_eq!(rendered, "#.\n.#\n");
+        assert_eq!(Grid::<u8>::default().to_string(), "");
+    }
+
+    #[test]
+    fn from_fn_and_rows() {
+        let grid = Grid::from_fn(3, 2, |p| p.x + 10 * p.y);
+        assert_eq!(
+            Grid::from_rows(vec![vec![0, 1, 2], vec![10, 11, 12]]),
+            Some(grid)
+        );
+        assert_eq!(Grid::from_rows(vec![vec![0, 1], vec![10]]), None);
+    }
+}
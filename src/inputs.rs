@@ -0,0 +1,493 @@
+use crate::runner::Error;
+use flate2::read::GzDecoder;
+use sha2::{Digest, Sha256};
+use std::fmt;
+use std::fs::{self, File};
+use std::io::{Read, Write};
+use std::net::TcpStream;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+/// Environment variable holding the name of the user whose inputs to use.
+pub const USER_VAR: &str = "AOC_USER";
+/// Environment variable holding the session token used to download inputs.
+pub const SESSION_VAR: &str = "AOC_SESSION";
+/// Environment variable holding the base URL to download inputs from.
+pub const URL_VAR: &str = "AOC_INPUT_URL";
+/// Environment variable holding the directory where downloads are cached.
+pub const CACHE_DIR_VAR: &str = "AOC_CACHE_DIR";
+
+// Layouts using `{user}` are skipped when no user is given, so the most
+// specific layouts come first.
+const DEFAULT_LAYOUTS: [&str; 2] = ["{user}/day{day}.txt", "day{day}.txt"];
+
+const ARCHIVE_EXTENSIONS: [&str; 3] = [".tar", ".tar.gz", ".tgz"];
+
+/// A session token, kept out of `Debug` output.
+#[derive(Clone, PartialEq, Eq)]
+pub struct Session(String);
+
+impl Session {
+    pub fn new(token: impl Into<String>) -> Self {
+        Session(token.into())
+    }
+
+    /// An identifier of the account the token belongs to, which does not give
+    /// the token away.
+    pub fn account(&self) -> String {
+        format!("{:x}", Sha256::digest(self.0.as_bytes()))[..16].to_string()
+    }
+}
+
+impl fmt::Debug for Session {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "Session({})", self.account())
+    }
+}
+
+/// Finds the input of a day, trying in turn each layout (a path relative to
+/// the input directory, such as `{user}/day{day}.txt`):
+///
+/// - as a file in the input directory, possibly gzip compressed
+///   (`day1.txt.gz`),
+/// - as an entry of the archives (`.tar`, `.tar.gz` or `.tgz`) found in the
+///   input directory or added with `with_archive`.
+///
+/// Failing that, with a session token, it looks for an input downloaded
+/// before in the cache directory, then downloads it from
+/// `{url}/day/{day}/input` and caches it. Only plain `http://` URLs are
+/// supported, such as that of a local server standing in for the real one.
+#[derive(Debug, Clone)]
+pub struct InputProvider {
+    dir: PathBuf,
+    layouts: Vec<String>,
+    archives: Vec<PathBuf>,
+    user: Option<String>,
+    url: Option<String>,
+    session: Option<Session>,
+    cache_dir: PathBuf,
+}
+
+impl InputProvider {
+    pub fn new(dir: impl Into<PathBuf>) -> Self {
+        let dir = dir.into();
+        InputProvider {
+            cache_dir: dir.join(".cache"),
+            dir,
+            layouts: DEFAULT_LAYOUTS.map(String::from).to_vec(),
+            archives: Vec::new(),
+            user: None,
+            url: None,
+            session: None,
+        }
+    }
+
+    /// A provider configured from the `AOC_*` environment variables.
+    pub fn from_env(dir: impl Into<PathBuf>) -> Self {
+        let var = |name| std::env::var(name).ok().filter(|v| !v.is_empty());
+        let mut provider = InputProvider::new(dir);
+        provider.user = var(USER_VAR);
+        provider.url = var(URL_VAR);
+        provider.session = var(SESSION_VAR).map(Session);
+        if let Some(cache_dir) = var(CACHE_DIR_VAR) {
+            provider.cache_dir = PathBuf::from(cache_dir);
+        }
+
+        provider
+    }
+
+    pub fn with_user(mut self, user: impl Into<String>) -> Self {
+        self.user = Some(user.into());
+        self
+    }
+
+    /// Replace the layouts, in which `{day}` and `{user}` are substituted.
+    pub fn with_layouts(mut self, layouts: Vec<String>) -> Self {
+        self.layouts = layouts;
+        self
+    }
+
+    pub fn with_archive(mut self, path: impl Into<PathBuf>) -> Self {
+        self.archives.push(path.into());
+        self
+    }
+
+    pub fn with_url(mut self, url: impl Into<String>) -> Self {
+        self.url = Some(url.into());
+        self
+    }
+
+    pub fn with_session(mut self, session: Session) -> Self {
+        self.session = Some(session);
+        self
+    }
+
+    pub fn with_cache_dir(mut self, path: impl Into<PathBuf>) -> Self {
+        self.cache_dir = path.into();
+        self
+    }
+
+    pub fn read(&self, day: u8) -> Result<String, Error> {
+        let archives = self.archives();
+        let mut tried = Vec::new();
+
+        for layout in &self.layouts {
+            let relative = match self.expand(layout, day)? {
+                Some(relative) => relative,
+                None => continue,
+            };
+
+            let path = self.dir.join(&relative);
+            if let Some(input) = read_file(&path)? {
+                return Ok(input);
+            }
+            tried.push(path.display().to_string());
+
+            for archive in &archives {
+                if let Some(input) = read_archive_entry(archive, &relative)? {
+                    return Ok(input);
+                }
+                tried.push(format!("{}:{}", archive.display(), relative));
+            }
+        }
+
+        if let Some(session) = &self.session {
+            let cached = self.cache_path(session, day);
+            if let Some(input) = read_file(&cached)? {
+                return Ok(input);
+            }
+
+            if let Some(url) = &self.url {
+                let input = download(url, day, session)?;
+                write_file(&cached, &input)?;
+                return Ok(input);
+            }
+        }
+
+        let mut message = format!("Could not find the input of day {}", day);
+        if !tried.is_empty() {
+            message += &format!(", tried {}", tried.join(", "));
+        }
+        if self.session.is_none() || self.url.is_none() {
+            message += &format!(" (set {} and {} to download it)", SESSION_VAR, URL_VAR);
+        }
+
+        Err(message.into())
+    }
+
+    // Returns `None` if the layout needs a user but there is none. The user
+    // must name a single directory inside the input directory.
+    fn expand(&self, layout: &str, day: u8) -> Result<Option<String>, Error> {
+        let layout = layout.replace("{day}", &day.to_string());
+        match &self.user {
+            Some(user) if user.is_empty() || user == ".." || user.contains(['/', '\\']) => {
+                Err(format!("Invalid user name: {:?}", user).into())
+            }
+            Some(user) => Ok(Some(layout.replace("{user}", user))),
+            None if layout.contains("{user}") => Ok(None),
+            None => Ok(Some(layout)),
+        }
+    }
+
+    // The archives added explicitly, then those in the input directory.
+    fn archives(&self) -> Vec<PathBuf> {
+        let mut found: Vec<PathBuf> = match fs::read_dir(&self.dir) {
+            Ok(entries) => entries
+                .filter_map(|entry| entry.ok().map(|e| e.path()))
+                .filter(|path| path.is_file() && is_archive(path))
+                .collect(),
+            Err(_) => Vec::new(),
+        };
+        found.sort();
+
+        let mut archives = self.archives.clone();
+        archives.extend(found.into_iter().filter(|p| !self.archives.contains(p)));
+        archives
+    }
+
+    // Downloads are cached per account, since inputs differ between them.
+    fn cache_path(&self, session: &Session, day: u8) -> PathBuf {
+        self.cache_dir
+            .join(session.account())
+            .join(format!("day{}.txt", day))
+    }
+}
+
+fn is_archive(path: &Path) -> bool {
+    let name = path.to_string_lossy();
+    ARCHIVE_EXTENSIONS.iter().any(|ext| name.ends_with(ext))
+}
+
+fn is_gzip(path: &Path) -> bool {
+    let name = path.to_string_lossy();
+    name.ends_with(".gz") || name.ends_with(".tgz")
+}
+
+// Read `path`, or its gzip compressed version `path.gz`, if either exists.
+fn read_file(path: &Path) -> Result<Option<String>, Error> {
+    let gzipped = PathBuf::from(format!("{}.gz", path.display()));
+    let (path, reader): (&Path, Box<dyn Read>) = if path.is_file() {
+        (path, Box::new(open(path)?))
+    } else if gzipped.is_file() {
+        (&gzipped, Box::new(GzDecoder::new(open(&gzipped)?)))
+    } else {
+        return Ok(None);
+    };
+
+    read_to_string(path, reader).map(Some)
+}
+
+fn read_archive_entry(archive: &Path, relative: &str) -> Result<Option<String>, Error> {
+    let file = open(archive)?;
+    let reader: Box<dyn Read> = if is_gzip(archive) {
+        Box::new(GzDecoder::new(file))
+    } else {
+        Box::new(file)
+    };
+
+    let error = |e| format!("Could not read {}: {}", archive.display(), e);
+    let mut entries = tar::Archive::new(reader);
+    for entry in entries.entries().map_err(error)? {
+        let entry = entry.map_err(error)?;
+        let path = entry.path().map_err(error)?;
+        if path.components().eq(Path::new(relative).components()) {
+            let name = format!("{}:{}", archive.display(), relative);
+            return read_to_string(Path::new(&name), entry).map(Some);
+        }
+    }
+
+    Ok(None)
+}
+
+fn open(path: &Path) -> Result<File, Error> {
+    File::open(path).map_err(|e| format!("Could not read {}: {}", path.display(), e).into())
+}
+
+fn read_to_string(path: &Path, mut reader: impl Read) -> Result<String, Error> {
+    let mut input = String::new();
+    reader
+        .read_to_string(&mut input)
+        .map_err(|e| format!("Could not read {}: {}", path.display(), e))?;
+    Ok(input)
+}
+
+fn write_file(path: &Path, input: &str) -> Result<(), Error> {
+    let error = |e| format!("Could not write {}: {}", path.display(), e);
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent).map_err(error)?;
+    }
+    fs::write(path, input).map_err(error)?;
+    Ok(())
+}
+
+fn download(url: &str, day: u8, session: &Session) -> Result<String, Error> {
+    let rest = url
+        .strip_prefix("http://")
+        .ok_or_else(|| format!("Only http:// URLs are supported: {}", url))?;
+    let (host, base) = rest.split_once('/').unwrap_or((rest, ""));
+    let base = base.trim_matches('/');
+    let path = if base.is_empty() {
+        format!("/day/{}/input", day)
+    } else {
+        format!("/{}/day/{}/input", base, day)
+    };
+    let address = if host.contains(':') {
+        host.to_string()
+    } else {
+        format!("{}:80", host)
+    };
+
+    let error = |e| format!("Could not download http://{}{}: {}", host, path, e);
+    let mut stream = TcpStream::connect(&address).map_err(error)?;
+    stream
+        .set_read_timeout(Some(Duration::from_secs(30)))
+        .map_err(error)?;
+    write!(
+        stream,
+        "GET {} HTTP/1.1\r\nHost: {}\r\nCookie: session={}\r\n\
+         User-Agent: advent-of-code-2021\r\nConnection: close\r\n\r\n",
+        path, host, session.0
+    )
+    .map_err(error)?;
+
+    let mut response = Vec::new();
+    stream.read_to_end(&mut response).map_err(error)?;
+    parse_response(&response)
+        .map_err(|e| format!("Could not download http://{}{}: {}", host, path, e).into())
+}
+
+fn parse_response(response: &[u8]) -> Result<String, String> {
+    let response = std::str::from_utf8(response).map_err(|_| "Invalid UTF-8 in the response")?;
+    let (head, body) = response
+        .split_once("\r\n\r\n")
+        .ok_or("Truncated response")?;
+
+    let mut lines = head.lines();
+    let status = lines.next().unwrap_or_default();
+    if status.split_whitespace().nth(1) != Some("200") {
+        return Err(format!("Unexpected status: {}", status));
+    }
+
+    let chunked = lines.any(|line| {
+        line.split_once(':').is_some_and(|(name, value)| {
+            name.trim().eq_ignore_ascii_case("transfer-encoding")
+                && value.trim().eq_ignore_ascii_case("chunked")
+        })
+    });
+    if chunked {
+        decode_chunks(body)
+    } else {
+        Ok(body.to_string())
+    }
+}
+
+fn decode_chunks(mut body: &str) -> Result<String, String> {
+    let mut decoded = String::new();
+    loop {
+        let (size, rest) = body.split_once("\r\n").ok_or("Truncated chunk")?;
+        // Ignore the chunk extensions.
+        let size = size.split(';').next().unwrap_or_default().trim();
+        let size = usize::from_str_radix(size, 16)
+            .map_err(|_| format!("Invalid chunk size: {:?}", size))?;
+        if size == 0 {
+            return Ok(decoded);
+        }
+
+        decoded.push_str(rest.get(..size).ok_or("Truncated chunk")?);
+        body = rest[size..].strip_prefix("\r\n").ok_or("Truncated chunk")?;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use flate2::write::GzEncoder;
+    use flate2::Compression;
+    use std::io::BufRead;
+    use std::net::TcpListener;
+
+    // A new empty directory for a test.
+    fn test_dir(name: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("aoc-inputs-{}-{}", std::process::id(), name));
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(&dir).unwrap();
+        dir
+    }
+
+    fn gzip(data: &[u8]) -> Vec<u8> {
+        let mut encoder = GzEncoder::new(Vec::new(), Compression::default());
+        encoder.write_all(data).unwrap();
+        encoder.finish().unwrap()
+    }
+
+    #[test]
+    fn directory_layouts() {
+        let dir = test_dir("layouts");
+        fs::create_dir(dir.join("alice")).unwrap();
+        fs::write(dir.join("day1.txt"), "shared").unwrap();
+        fs::write(dir.join("alice/day1.txt"), "alice").unwrap();
+        fs::write(dir.join("day2.txt.gz"), gzip(b"compressed")).unwrap();
+
+        let provider = InputProvider::new(&dir);
+        assert_eq!(provider.read(1).unwrap(), "shared");
+        assert_eq!(provider.read(2).unwrap(), "compressed");
+        let provider = provider.with_user("alice");
+        assert_eq!(provider.read(1).unwrap(), "alice");
+        assert_eq!(provider.with_user("bob").read(1).unwrap(), "shared");
+        for user in ["", "..", "../alice", "alice/..", "a\\b"] {
+            let error = InputProvider::new(&dir)
+                .with_user(user)
+                .read(1)
+                .unwrap_err();
+            assert_eq!(error.to_string(), format!("Invalid user name: {:?}", user));
+        }
+
+        let provider = InputProvider::new(&dir).with_layouts(vec![String::from("input{day}")]);
+        let error = provider.read(1).unwrap_err().to_string();
+        assert!(error.starts_with("Could not find the input of day 1, tried "));
+        assert!(error.contains("input1"));
+    }
+
+    #[test]
+    fn archives() {
+        let dir = test_dir("archives");
+        let mut builder = tar::Builder::new(GzEncoder::new(Vec::new(), Compression::default()));
+        for (path, data) in [("alice/day3.txt", "alice"), ("bob/day3.txt", "bob")] {
+            let mut header = tar::Header::new_gnu();
+            header.set_size(data.len() as u64);
+            header.set_mode(0o644);
+            header.set_cksum();
+            builder
+                .append_data(&mut header, path, data.as_bytes())
+                .unwrap();
+        }
+        let archive = builder.into_inner().unwrap().finish().unwrap();
+        fs::write(dir.join("inputs.tar.gz"), archive).unwrap();
+
+        let provider = InputProvider::new(&dir);
+        assert_eq!(provider.clone().with_user("bob").read(3).unwrap(), "bob");
+        assert_eq!(
+            provider.clone().with_user("alice").read(3).unwrap(),
+            "alice"
+        );
+        assert!(provider.read(3).is_err());
+    }
+
+    #[test]
+    fn download_and_cache() {
+        let dir = test_dir("download");
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let url = format!("http://{}/aoc/", listener.local_addr().unwrap());
+
+        // Serve a single request, in chunks.
+        let server = std::thread::spawn(move || {
+            let (stream, _) = listener.accept().unwrap();
+            let mut reader = std::io::BufReader::new(&stream);
+            let mut request = Vec::new();
+            loop {
+                let mut line = String::new();
+                reader.read_line(&mut line).unwrap();
+                if line.trim_end().is_empty() {
+                    break;
+                }
+                request.push(line.trim_end().to_string());
+            }
+            (&stream)
+                .write_all(
+                    b"HTTP/1.1 200 OK\r\nTransfer-Encoding: chunked\r\n\r\n\
+                      4\r\n1\n2\n\r\n2\r\n3\n\r\n0\r\n\r\n",
+                )
+                .unwrap();
+            request
+        });
+
+        let session = Session::new("secret");
+        let provider = InputProvider::new(&dir)
+            .with_url(url)
+            .with_session(session.clone());
+        assert_eq!(provider.read(4).unwrap(), "1\n2\n3\n");
+
+        let request = server.join().unwrap();
+        assert_eq!(request[0], "GET /aoc/day/4/input HTTP/1.1");
+        assert!(request.contains(&String::from("Cookie: session=secret")));
+
+        // The server is gone, so this comes from the cache.
+        assert_eq!(provider.read(4).unwrap(), "1\n2\n3\n");
+        let cached = dir.join(".cache").join(session.account()).join("day4.txt");
+        assert!(cached.is_file());
+        assert!(!format!("{:?}", provider).contains("secret"));
+    }
+
+    #[test]
+    fn responses() {
+        assert_eq!(
+            parse_response(b"HTTP/1.1 200 OK\r\nContent-Length: 3\r\n\r\n1\n2").unwrap(),
+            "1\n2"
+        );
+        assert_eq!(
+            parse_response(b"HTTP/1.1 404 Not Found\r\n\r\n").unwrap_err(),
+            "Unexpected status: HTTP/1.1 404 Not Found"
+        );
+        assert!(decode_chunks("5\r\n12").is_err());
+    }
+}
@@ -0,0 +1,214 @@
+use crate::bench::Allocations;
+use std::fmt;
+use std::ops::RangeInclusive;
+use std::str::FromStr;
+use std::time::{Duration, Instant};
+
+/// Error returned when reading or solving a day.
+pub type Error = Box<dyn std::error::Error>;
+
+pub const DAYS: RangeInclusive<u8> = 1..=25;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Part {
+    One,
+    Two,
+}
+
+impl Part {
+    /// The parts available on a given day: there is no second part on the last
+    /// day.
+    pub fn available(day: u8) -> &'static [Part] {
+        if day == *DAYS.end() {
+            &[Part::One]
+        } else {
+            &[Part::One, Part::Two]
+        }
+    }
+}
+
+impl fmt::Display for Part {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Part::One => write!(f, "1"),
+            Part::Two => write!(f, "2"),
+        }
+    }
+}
+
+impl From<Part> for u8 {
+    fn from(part: Part) -> Self {
+        match part {
+            Part::One => 1,
+            Part::Two => 2,
+        }
+    }
+}
+
+impl FromStr for Part {
+    type Err = String;
+
+    fn from_str(part: &str) -> Result<Self, Self::Err> {
+        match part {
+            "1" => Ok(Part::One),
+            "2" => Ok(Part::Two),
+            _ => Err(format!("Invalid part: {}", part)),
+        }
+    }
+}
+
+/// What solving a part returns: its answer, or the error preventing it.
+pub trait Outcome {
+    type Answer: fmt::Display;
+
+    fn into_answer(self) -> Result<Self::Answer, Error>;
+}
+
+macro_rules! answer_outcome {
+    ($($answer:ty),*) => {
+        $(
+            impl Outcome for $answer {
+                type Answer = $answer;
+
+                fn into_answer(self) -> Result<Self::Answer, Error> {
+                    Ok(self)
+                }
+            }
+        )*
+    };
+}
+
+answer_outcome!(i32, i64, u32, u64, usize, String, num_bigint::BigUint);
+
+impl<T, E> Outcome for Result<T, E>
+where
+    T: fmt::Display,
+    E: std::error::Error + 'static,
+{
+    type Answer = T;
+
+    fn into_answer(self) -> Result<Self::Answer, Error> {
+        self.map_err(Error::from)
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct Solution {
+    pub day: u8,
+    pub parse_time: Duration,
+    pub parse_allocations: Allocations,
+    pub answers: Vec<Answer>,
+}
+
+#[derive(Debug, Clone)]
+pub struct Answer {
+    pub part: Part,
+    pub value: String,
+    // Name of the type returned by the solver, without its path (e.g. `u64`
+    // or `String`).
+    pub kind: &'static str,
+    pub solve_time: Duration,
+    pub allocations: Allocations,
+}
+
+impl Answer {
+    fn timed<T: Outcome>(part: Part, solver: impl FnOnce() -> T) -> Result<Self, Error> {
+        let allocations = Allocations::now();
+        let start = Instant::now();
+        let outcome = solver();
+        let solve_time = start.elapsed();
+        let allocations = Allocations::now() - allocations;
+        let value = outcome
+            .into_answer()
+            .map_err(|e| format!("Part {}: {}", part, e))?;
+        let kind = std::any::type_name::<T::Answer>();
+
+        Ok(Answer {
+            part,
+            value: value.to_string(),
+            kind: kind.rsplit("::").next().unwrap_or(kind),
+            solve_time,
+            allocations,
+        })
+    }
+}
+
+// Parse the input once, then solve each requested part.
+macro_rules! solve {
+    ($day:expr, $module:ident, $input:expr, $parts:expr) => {{
+        let allocations = Allocations::now();
+        let start = Instant::now();
+        let input = crate::$module::parse($input)?;
+        let parse_time = start.elapsed();
+        let parse_allocations = Allocations::now() - allocations;
+        let answers = $parts
+            .iter()
+            .map(|&part| match part {
+                Part::One => Answer::timed(part, || crate::$module::part1(&input)),
+                Part::Two => Answer::timed(part, || crate::$module::part2(&input)),
+            })
+            .collect::<Result<_, Error>>()?;
+
+        Ok(Solution {
+            day: $day,
+            parse_time,
+            parse_allocations,
+            answers,
+        })
+    }};
+}
+
+/// Solve the requested `parts` of `day` for the given puzzle `input`, timing
+/// the parsing and each part separately. Allocations are only counted when
+/// `bench::CountingAllocator` is the global allocator.
+pub fn solve(day: u8, input: &str, parts: &[Part]) -> Result<Solution, Error> {
+    match day {
+        1 => solve!(day, day1, input, parts),
+        2 => solve!(day, day2, input, parts),
+        3 => solve!(day, day3, input, parts),
+        4 => solve!(day, day4, input, parts),
+        5 => solve!(day, day5, input, parts),
+        6 => solve!(day, day6, input, parts),
+        7 => solve!(day, day7, input, parts),
+        8 => solve!(day, day8, input, parts),
+        9 => solve!(day, day9, input, parts),
+        10 => solve!(day, day10, input, parts),
+        11 => solve!(day, day11, input, parts),
+        12 => solve!(day, day12, input, parts),
+        13 => solve!(day, day13, input, parts),
+        14 => solve!(day, day14, input, parts),
+        15 => solve!(day, day15, input, parts),
+        16 => solve!(day, day16, input, parts),
+        17 => solve!(day, day17, input, parts),
+        18 => solve!(day, day18, input, parts),
+        19 => solve!(day, day19, input, parts),
+        20 => solve!(day, day20, input, parts),
+        21 => solve!(day, day21, input, parts),
+        22 => solve!(day, day22, input, parts),
+        23 => solve!(day, day23, input, parts),
+        24 => solve!(day, day24, input, parts),
+        // There is no second part on the last day.
+        25 => {
+            let allocations = Allocations::now();
+            let start = Instant::now();
+            let input = crate::day25::parse(input)?;
+            let parse_time = start.elapsed();
+            let parse_allocations = Allocations::now() - allocations;
+            let answers = parts
+                .iter()
+                .map(|&part| match part {
+                    Part::One => Answer::timed(part, || crate::day25::part1(&input)),
+                    Part::Two => Err("There is no part 2 on the last day.".into()),
+                })
+                .collect::<Result<_, Error>>()?;
+
+            Ok(Solution {
+                day,
+                parse_time,
+                parse_allocations,
+                answers,
+            })
+        }
+        _ => Err(format!("Invalid day: {}", day).into()),
+    }
+}
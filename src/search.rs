@@ -0,0 +1,221 @@
+use std::cmp::Reverse;
+use std::collections::hash_map::Entry;
+use std::collections::{BinaryHeap, HashMap, VecDeque};
+use std::hash::Hash;
+use std::ops::Add;
+
+// Parent of the start node when reconstructing a path.
+const NO_PARENT: usize = usize::MAX;
+
+/// A path found by a search: the nodes from the start to the goal, both
+/// included, and the total cost of the steps between them.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Path<N, C> {
+    pub cost: C,
+    pub nodes: Vec<N>,
+}
+
+impl<N, C> Path<N, C> {
+    pub fn start(&self) -> &N {
+        &self.nodes[0]
+    }
+
+    pub fn goal(&self) -> &N {
+        &self.nodes[self.nodes.len() - 1]
+    }
+
+    /// The number of steps, one less than the number of nodes.
+    pub fn len(&self) -> usize {
+        self.nodes.len() - 1
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+}
+
+// The nodes seen by a search, each with the index of the node it was reached
+// from.
+struct Explored<N> {
+    nodes: Vec<N>,
+    parents: Vec<usize>,
+    indices: HashMap<N, usize>,
+}
+
+impl<N: Eq + Hash + Clone> Explored<N> {
+    fn new(start: N) -> Self {
+        Explored {
+            nodes: vec![start.clone()],
+            parents: vec![NO_PARENT],
+            indices: HashMap::from([(start, 0)]),
+        }
+    }
+
+    fn path<C>(&self, mut idx: usize, cost: C) -> Path<N, C> {
+        let mut nodes = Vec::new();
+        while idx != NO_PARENT {
+            nodes.push(self.nodes[idx].clone());
+            idx = self.parents[idx];
+        }
+        nodes.reverse();
+
+        Path { cost, nodes }
+    }
+}
+
+/// Breadth-first search from `start` to the first node satisfying `is_goal`,
+/// the cost being the number of steps.
+pub fn bfs<N, FN, IN, FG>(start: N, mut successors: FN, mut is_goal: FG) -> Option<Path<N, usize>>
+where
+    N: Eq + Hash + Clone,
+    FN: FnMut(&N) -> IN,
+    IN: IntoIterator<Item = N>,
+    FG: FnMut(&N) -> bool,
+{
+    let mut explored = Explored::new(start);
+    let mut to_visit = VecDeque::from([(0, 0)]);
+
+    while let Some((steps, idx)) = to_visit.pop_front() {
+        if is_goal(&explored.nodes[idx]) {
+            return Some(explored.path(idx, steps));
+        }
+
+        for next in successors(&explored.nodes[idx]) {
+            if let Entry::Vacant(e) = explored.indices.entry(next) {
+                let next_idx = explored.nodes.len();
+                explored.nodes.push(e.key().clone());
+                explored.parents.push(idx);
+                e.insert(next_idx);
+                to_visit.push_back((steps + 1, next_idx));
+            }
+        }
+    }
+
+    None
+}
+
+/// Dijkstra's algorithm from `start` to the first node satisfying `is_goal`,
+/// `successors` giving the cost of each step.
+pub fn dijkstra<N, C, FN, IN, FG>(start: N, successors: FN, is_goal: FG) -> Option<Path<N, C>>
+where
+    N: Eq + Hash + Clone,
+    C: Copy + Ord + Default + Add<Output = C>,
+    FN: FnMut(&N) -> IN,
+    IN: IntoIterator<Item = (N, C)>,
+    FG: FnMut(&N) -> bool,
+{
+    astar(start, successors, |_| C::default(), is_goal)
+}
+
+/// A* search from `start` to the first node satisfying `is_goal`. The
+/// `heuristic` estimates the remaining cost to a goal: the path found is the
+/// cheapest one as long as it never overestimates it.
+pub fn astar<N, C, FN, IN, FH, FG>(
+    start: N,
+    mut successors: FN,
+    mut heuristic: FH,
+    mut is_goal: FG,
+) -> Option<Path<N, C>>
+where
+    N: Eq + Hash + Clone,
+    C: Copy + Ord + Default + Add<Output = C>,
+    FN: FnMut(&N) -> IN,
+    IN: IntoIterator<Item = (N, C)>,
+    FH: FnMut(&N) -> C,
+    FG: FnMut(&N) -> bool,
+{
+    let mut to_visit = BinaryHeap::new();
+    to_visit.push(Reverse((heuristic(&start), C::default(), 0)));
+    let mut explored = Explored::new(start);
+    let mut costs = vec![C::default()];
+
+    while let Some(Reverse((_, cost, idx))) = to_visit.pop() {
+        // A cheaper way to this node has been found since.
+        if cost > costs[idx] {
+            continue;
+        }
+
+        if is_goal(&explored.nodes[idx]) {
+            return Some(explored.path(idx, cost));
+        }
+
+        for (next, step) in successors(&explored.nodes[idx]) {
+            let next_cost = cost + step;
+            let next_idx = match explored.indices.entry(next) {
+                Entry::Vacant(e) => {
+                    let next_idx = explored.nodes.len();
+                    explored.nodes.push(e.key().clone());
+                    explored.parents.push(idx);
+                    costs.push(next_cost);
+                    e.insert(next_idx);
+                    next_idx
+                }
+                Entry::Occupied(e) if next_cost < costs[*e.get()] => {
+                    let next_idx = *e.get();
+                    explored.parents[next_idx] = idx;
+                    costs[next_idx] = next_cost;
+                    next_idx
+                }
+                Entry::Occupied(_) => continue,
+            };
+
+            let estimate = next_cost + heuristic(&explored.nodes[next_idx]);
+            to_visit.push(Reverse((estimate, next_cost, next_idx)));
+        }
+    }
+
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // A small weighted graph where the direct edges are not the cheapest.
+    fn edges(node: &char) -> Vec<(char, u32)> {
+        match node {
+            'a' => vec![('b', 7), ('c', 9), ('f', 14)],
+            'b' => vec![('a', 7), ('c', 10), ('d', 15)],
+            'c' => vec![('a', 9), ('b', 10), ('d', 11), ('f', 2)],
+            'd' => vec![('b', 15), ('c', 11), ('e', 6)],
+            'e' => vec![('d', 6), ('f', 9)],
+            'f' => vec![('a', 14), ('c', 2), ('e', 9)],
+            _ => vec![],
+        }
+    }
+
+    #[test]
+    fn bfs_fewest_steps() {
+        let path = bfs('a', |n| edges(n).into_iter().map(|(n, _)| n), |n| *n == 'e').unwrap();
+        assert_eq!((path.cost, path.nodes), (2, vec!['a', 'f', 'e']));
+        assert_eq!(
+            bfs('a', |n| edges(n).into_iter().map(|(n, _)| n), |n| *n == 'z'),
+            None
+        );
+    }
+
+    #[test]
+    fn dijkstra_cheapest() {
+        let path = dijkstra('a', edges, |n| *n == 'e').unwrap();
+        assert_eq!(
+            (path.cost, &path.nodes[..]),
+            (20, &['a', 'c', 'f', 'e'][..])
+        );
+        assert_eq!((path.start(), path.goal(), path.len()), (&'a', &'e', 3));
+
+        let path = dijkstra('a', edges, |n| *n == 'a').unwrap();
+        assert_eq!((path.cost, &path.nodes[..]), (0, &['a'][..]));
+        assert!(path.is_empty());
+    }
+
+    #[test]
+    fn astar_on_a_line() {
+        // Moving by 1 or 3 along a line, towards 10, costs 1 or 2.
+        let successors = |&n: &i32| [(n + 1, 1), (n + 3, 2), (n - 1, 1)];
+        let heuristic = |&n: &i32| (10 - n).max(0) / 3;
+        let path = astar(0, successors, heuristic, |&n| n == 10).unwrap();
+        assert_eq!(path.cost, 7);
+        assert_eq!(path.len(), 4);
+        assert_eq!(dijkstra(0, successors, |&n| n == 10).unwrap().cost, 7);
+    }
+}